@@ -25,10 +25,19 @@ fn lib_benchmark(c: &mut Criterion) {
     group.bench_function("ping", |b| {
         b.iter(|| mvdparser::all::ping_per_player_number(&data))
     });
+    group.bench_function("network_stats", |b| {
+        b.iter(|| mvdparser::all::network_stats(&data))
+    });
 
     group.bench_function("frags", |b| {
         b.iter(|| mvdparser::all::frags_per_player_name(&data))
     });
+    group.bench_function("scoreboard_timeline", |b| {
+        b.iter(|| mvdparser::all::scoreboard_timeline(&data))
+    });
+    group.bench_function("frag_events", |b| {
+        b.iter(|| mvdparser::all::frag_events_per_player_number(&data))
+    });
     group.bench_function("prints", |b| b.iter(|| mvdparser::all::prints(&data)));
 
     group.bench_function("clientinfo", |b| {
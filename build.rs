@@ -0,0 +1,16 @@
+// generates the C header (OUT_DIR/mvdparser.h) for the capi feature
+fn main() {
+    #[cfg(feature = "capi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+
+        cbindgen::generate_with_config(&crate_dir, config)
+            .expect("unable to generate C header")
+            .write_to_file(format!("{}/mvdparser.h", out_dir));
+
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mvdparser::all::*;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = is_valid(data);
+    let _ = serverinfo(data);
+    let _ = clientinfo(data);
+    let _ = clients(data);
+    let _ = mvdparser::filename(data);
+    let _ = server(data);
+    let _ = matchdate(data);
+    let _ = timestamp(data);
+    let _ = ktxstats_v3(data);
+    let _ = recover(data);
+    let _ = redact(data, &RedactOptions::default());
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mvdparser::all::*;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = countdown_duration(data);
+    let _ = demo_duration(data);
+    let _ = match_duration(data);
+    let _ = pauses(data);
+    let _ = overtime(data);
+});
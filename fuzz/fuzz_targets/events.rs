@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mvdparser::all::*;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = prints(data);
+    let _ = is_aborted(data);
+    let _ = abort_vote(data);
+    let _ = vote_events(data);
+    let _ = admin_events(data);
+    let _ = ctf_stats(data);
+    let _ = rune_runs(data);
+    let _ = rounds(data);
+    let _ = race_runs(data);
+    let _ = hoonymode_points(data);
+    let _ = result(data);
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mvdparser::all::*;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = players_from_parsing(data);
+    let _ = players(data);
+    let _ = teams(data);
+    let _ = frags_per_player_name(data);
+    let _ = ping_per_player_number(data);
+    let _ = network_stats(data);
+    let _ = scoreboard_timeline(data);
+    let _ = name_timeline(data);
+});
@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use bstr::ByteSlice;
 
+use crate::votes::{vote_events, VoteKind, VoteStatus};
+
 const MATCH_STOPPED_PRINT: [u8; 0x22] = [
     0x08, 0x02, 0xCD, 0xE1, 0xF4, 0xE3, 0xE8, 0x20, 0xF3, 0xF4, 0xEF, 0xF0, 0xF0, 0xE5, 0xE4, 0x20,
     0xE2, 0xF9, 0x20, 0xED, 0xE1, 0xEA, 0xEF, 0xF2, 0xE9, 0xF4, 0xF9, 0x20, 0xF6, 0xEF, 0xF4, 0xE5,
@@ -10,6 +14,44 @@ pub fn is_aborted(data: &[u8]) -> bool {
     data.find(MATCH_STOPPED_PRINT).is_some()
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AbortVote {
+    pub time: Duration,
+    pub called_by: Option<String>,
+    pub voters: Vec<String>,
+}
+
+pub fn abort_vote(data: &[u8]) -> Option<AbortVote> {
+    let mut voters: Vec<String> = vec![];
+
+    for event in vote_events(data) {
+        if event.kind != VoteKind::StopMatch {
+            continue;
+        }
+
+        match event.status {
+            VoteStatus::Passed => {
+                return Some(AbortVote {
+                    time: event.time,
+                    called_by: voters.first().cloned(),
+                    voters,
+                });
+            }
+            VoteStatus::Failed => voters.clear(),
+            VoteStatus::Cast => {
+                if let Some(name) = event.name {
+                    if !voters.contains(&name) {
+                        voters.push(name);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::read;
@@ -17,6 +59,7 @@ mod tests {
     use anyhow::Result;
 
     use super::*;
+    use crate::testdemo::{print, to_all};
 
     #[test]
     fn test_is_aborted() -> Result<()> {
@@ -44,4 +87,58 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_abort_vote() -> Result<()> {
+        assert_eq!(
+            abort_vote(&read(
+                "tests/files/2on2_sf_vs_red[frobodm2]220104-0915.mvd"
+            )?),
+            Some(AbortVote {
+                time: Duration::from_millis(196690),
+                called_by: Some("Final".to_string()),
+                voters: vec!["Final".to_string()],
+            })
+        );
+        assert_eq!(
+            abort_vote(&read(
+                "tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd"
+            )?),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_abort_vote_failed_then_passed() {
+        let data = [
+            to_all(0, &print(2, b"FOO votes for stopping the match\n")),
+            to_all(100, &print(2, b"The voting has timed out.\n")),
+            to_all(100, &print(2, b"BAR votes for stopping the match\n")),
+            to_all(100, &print(2, b"FOO votes for stopping the match\n")),
+            to_all(0, &print(2, b"Match stopped by majority vote\n")),
+        ]
+        .concat();
+
+        assert_eq!(
+            abort_vote(&data),
+            Some(AbortVote {
+                time: Duration::from_millis(300),
+                called_by: Some("BAR".to_string()),
+                voters: vec!["BAR".to_string(), "FOO".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_abort_vote_from_chat() {
+        let data = [
+            to_all(0, &print(3, b"FOO votes for stopping the match\n")),
+            to_all(0, &print(3, b"Match stopped by majority vote\n")),
+        ]
+        .concat();
+
+        assert_eq!(abort_vote(&data), None);
+    }
 }
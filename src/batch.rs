@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fs::read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::aborted::is_aborted;
+use crate::duration::match_duration;
+use crate::frags::frags_per_player_name;
+#[cfg(feature = "mmap")]
+use crate::mmap::mmap_file;
+use crate::overtime::{overtime, OvertimeInfo};
+use crate::player::Player;
+use crate::players::players;
+use crate::serverinfo::serverinfo;
+use crate::team::Team;
+use crate::teams::teams;
+use crate::timestamp::timestamp;
+use crate::validate::{has_end_of_demo_print, is_valid};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Extractor {
+    Summary,
+    Players,
+    Teams,
+    Frags,
+    Validation,
+}
+
+impl Extractor {
+    pub const ALL: [Extractor; 5] = [
+        Extractor::Summary,
+        Extractor::Players,
+        Extractor::Teams,
+        Extractor::Frags,
+        Extractor::Validation,
+    ];
+}
+
+// how files are loaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadMode {
+    #[default]
+    Read,
+    // files must not be modified or truncated during the batch, see mmap_file
+    #[cfg(feature = "mmap")]
+    Mmap,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    pub map: Option<String>,
+    pub mode: Option<String>,
+    pub hostname: Option<String>,
+    pub matchtag: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub duration: Option<Duration>,
+    pub overtime: Option<OvertimeInfo>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Validation {
+    pub is_valid: bool,
+    pub is_truncated: bool, // missing EndOfDemo
+    pub is_aborted: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchItem {
+    pub path: PathBuf,
+    pub summary: Option<Summary>,
+    pub players: Option<Vec<Player>>,
+    pub teams: Option<Vec<Team>>,
+    pub frags: Option<HashMap<String, i32>>,
+    pub validation: Option<Validation>,
+    pub errors: Vec<String>, // e.g. "players: Unable to find clientinfo strings"
+}
+
+// results in the same order as paths
+pub fn batch<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    extractors: &[Extractor],
+    mode: ReadMode,
+) -> Vec<BatchItem> {
+    #[cfg(feature = "rayon")]
+    let iter = paths.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let iter = paths.iter();
+
+    iter.map(|path| extract(path.as_ref(), extractors, mode))
+        .collect()
+}
+
+// calls f as soon as a file is done, in any order if parallel
+pub fn batch_for_each<P, F>(paths: &[P], extractors: &[Extractor], mode: ReadMode, f: F)
+where
+    P: AsRef<Path> + Sync,
+    F: Fn(BatchItem) + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    let iter = paths.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let iter = paths.iter();
+
+    iter.for_each(|path| f(extract(path.as_ref(), extractors, mode)));
+}
+
+pub fn extract(path: &Path, extractors: &[Extractor], mode: ReadMode) -> BatchItem {
+    let mut item = BatchItem {
+        path: path.to_path_buf(),
+        ..Default::default()
+    };
+
+    let res = match mode {
+        ReadMode::Read => read(path).map(|data| extract_data(&data, extractors, &mut item)),
+        #[cfg(feature = "mmap")]
+        // safety: choosing ReadMode::Mmap is the caller's promise that files are not
+        // modified while the batch runs, each map is dropped before extract returns
+        ReadMode::Mmap => {
+            unsafe { mmap_file(path) }.map(|data| extract_data(&data, extractors, &mut item))
+        }
+    };
+
+    if let Err(err) = res {
+        item.errors.push(format!("read: {}", err));
+    }
+
+    item
+}
+
+fn extract_data(data: &[u8], extractors: &[Extractor], item: &mut BatchItem) {
+    for extractor in extractors {
+        let res = match extractor {
+            Extractor::Summary => summary(data).map(|v| item.summary = Some(v)),
+            Extractor::Players => players(data).map(|v| item.players = Some(v)),
+            Extractor::Teams => teams(data).map(|v| item.teams = Some(v)),
+            Extractor::Frags => {
+                item.frags = Some(frags_per_player_name(data));
+                Ok(())
+            }
+            Extractor::Validation => {
+                item.validation = Some(validation(data));
+                Ok(())
+            }
+        };
+
+        if let Err(err) = res {
+            let name = format!("{:?}", extractor).to_lowercase();
+            item.errors.push(format!("{}: {}", name, err));
+        }
+    }
+}
+
+pub fn summary(data: &[u8]) -> crate::error::Result<Summary> {
+    let settings = serverinfo(data)?;
+
+    Ok(Summary {
+        map: settings.map,
+        mode: settings.mode,
+        hostname: settings.hostname,
+        matchtag: settings.matchtag,
+        timestamp: timestamp(data).ok(),
+        duration: match_duration(data).ok(),
+        overtime: overtime(data),
+    })
+}
+
+pub fn validation(data: &[u8]) -> Validation {
+    Validation {
+        is_valid: is_valid(data),
+        is_truncated: !has_end_of_demo_print(data),
+        is_aborted: is_aborted(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_batch() -> Result<()> {
+        let paths = [
+            "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd",
+            "tests/files/missing.mvd",
+            "tests/files/ffa_5[dm4]20240501-1229.mvd",
+        ];
+        let items = batch(&paths, &Extractor::ALL, ReadMode::Read);
+        assert_eq!(items.len(), 3);
+
+        {
+            let item = &items[0];
+            assert_eq!(item.path, PathBuf::from(paths[0]));
+            assert!(item.errors.is_empty());
+
+            let summary = item.summary.clone().unwrap();
+            assert_eq!(summary.map, Some("povdmm4".to_string()));
+            assert_eq!(summary.duration, Some(Duration::from_secs(180)));
+            assert_eq!(summary.overtime, None);
+            assert_eq!(item.players.as_ref().map(|p| p.len()), Some(2));
+            assert_eq!(item.teams.as_ref().map(|t| t.len()), Some(2));
+            assert_eq!(item.frags.as_ref().and_then(|f| f.get("eQu")), Some(&19));
+            assert_eq!(
+                item.validation,
+                Some(Validation {
+                    is_valid: true,
+                    is_truncated: false,
+                    is_aborted: false,
+                })
+            );
+        }
+        {
+            let item = &items[1];
+            assert_eq!(item.errors.len(), 1);
+            assert!(item.errors[0].starts_with("read: "));
+            assert_eq!(item.summary, None);
+        }
+        {
+            let item = &items[2];
+            assert_eq!(item.summary.clone().unwrap().mode, Some("ffa".to_string()));
+            assert!(item.validation.unwrap().is_aborted);
+            assert_eq!(item.players.as_ref().map(|p| p.len()), Some(5));
+            assert!(item.errors.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_for_each() -> Result<()> {
+        let paths = vec![
+            "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd",
+            "tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd",
+        ];
+        let items = std::sync::Mutex::new(vec![]);
+        batch_for_each(&paths, &[Extractor::Validation], ReadMode::Read, |item| {
+            items.lock().unwrap().push(item);
+        });
+
+        let mut items = items.into_inner()?;
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(items.len(), 2);
+        assert!(items
+            .iter()
+            .all(|i| i.validation.is_some_and(|v| v.is_valid)));
+        assert!(items.iter().all(|i| i.summary.is_none()));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_batch_mmap() {
+        let paths = [
+            "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd",
+            "tests/files/missing.mvd",
+        ];
+        let items = batch(&paths, &Extractor::ALL, ReadMode::Mmap);
+
+        assert_eq!(
+            items[0],
+            extract(Path::new(paths[0]), &Extractor::ALL, ReadMode::Read)
+        );
+        assert!(items[1].errors[0].starts_with("read: "));
+    }
+}
@@ -0,0 +1,666 @@
+use std::fs::read;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mvdparser::all::*;
+use quake_text::{bytestr, unicode};
+use serde_json::{json, Value};
+
+#[derive(Parser)]
+#[command(
+    name = "mvdparser",
+    version,
+    about = "Extract information from QuakeWorld MVD demos"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    format: FormatArgs,
+}
+
+#[derive(Args)]
+struct FormatArgs {
+    /// Output as JSON
+    #[arg(long, global = true, conflicts_with_all = ["csv", "table"])]
+    json: bool,
+
+    /// Output as CSV
+    #[arg(long, global = true, conflicts_with = "table")]
+    csv: bool,
+
+    /// Output as table (default)
+    #[arg(long, global = true)]
+    table: bool,
+}
+
+#[derive(Args)]
+struct Files {
+    /// Demo files or glob patterns
+    #[arg(required = true)]
+    files: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Map, mode, hostname, timestamp and duration
+    Info(Files),
+    /// Players
+    Players(Files),
+    /// Teams
+    Teams(Files),
+    /// Frags per player
+    Frags(Files),
+    /// All prints
+    Prints(Files),
+    /// Chat messages
+    Chat(Files),
+    /// Stats generated by KTX
+    Ktxstats(Files),
+    /// Demo status (valid, aborted, truncated)
+    Validate(Files),
+    /// Countdown, match and demo duration
+    Duration(Files),
+    /// Extract from all demos in a directory (recursive) in parallel
+    Index(IndexArgs),
+}
+
+#[derive(Args)]
+struct IndexArgs {
+    /// Directory to search for demos
+    dir: PathBuf,
+
+    /// Output one JSON object per demo as soon as it is done
+    #[arg(long)]
+    jsonl: bool,
+
+    /// Extractors to run
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = ExtractorArg::all())]
+    extract: Vec<ExtractorArg>,
+
+    /// Memory map files instead of reading them, files must not change during the run
+    #[arg(long)]
+    mmap: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExtractorArg {
+    Summary,
+    Players,
+    Teams,
+    Frags,
+    Validation,
+}
+
+impl ExtractorArg {
+    fn all() -> Vec<Self> {
+        ExtractorArg::value_variants().to_vec()
+    }
+}
+
+impl From<ExtractorArg> for Extractor {
+    fn from(value: ExtractorArg) -> Self {
+        match value {
+            ExtractorArg::Summary => Extractor::Summary,
+            ExtractorArg::Players => Extractor::Players,
+            ExtractorArg::Teams => Extractor::Teams,
+            ExtractorArg::Frags => Extractor::Frags,
+            ExtractorArg::Validation => Extractor::Validation,
+        }
+    }
+}
+
+// ordered by severity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Status {
+    Valid,
+    Aborted,
+    Truncated,
+    Invalid,
+    Error,
+}
+
+impl Status {
+    fn from_data(data: &[u8]) -> Self {
+        if serverinfo(data).is_err() || !has_clients(data) {
+            Status::Invalid
+        } else if !has_end_of_demo_print(data) {
+            Status::Truncated
+        } else if is_aborted(data) {
+            Status::Aborted
+        } else {
+            Status::Valid
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Valid => "valid",
+            Status::Aborted => "aborted",
+            Status::Truncated => "truncated",
+            Status::Invalid => "invalid",
+            Status::Error => "error",
+        }
+    }
+
+    fn exit_code(&self) -> u8 {
+        match self {
+            Status::Valid => 0,
+            Status::Error => 1,
+            Status::Invalid => 3,
+            Status::Truncated => 4,
+            Status::Aborted => 5,
+        }
+    }
+}
+
+type Run = fn(&[u8], Status) -> mvdparser::Result<Output>;
+
+struct Output {
+    json: Value,
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Command::Index(args) = &cli.command {
+        return index(args, &cli.format);
+    }
+
+    let (files, run): (&Files, Run) = match &cli.command {
+        Command::Info(f) => (f, info),
+        Command::Players(f) => (f, players_output),
+        Command::Teams(f) => (f, teams_output),
+        Command::Frags(f) => (f, frags),
+        Command::Prints(f) => (f, |data, _| Ok(prints_output(data, false))),
+        Command::Chat(f) => (f, |data, _| Ok(prints_output(data, true))),
+        Command::Ktxstats(f) => (f, ktxstats),
+        Command::Validate(f) => (f, |_, status| Ok(validate(status))),
+        Command::Duration(f) => (f, durations),
+        Command::Index(_) => unreachable!(),
+    };
+
+    let mut worst = Status::Valid;
+    let mut outputs: Vec<(String, Output)> = vec![];
+
+    for path in expand_paths(&files.files, &mut worst) {
+        let name = path.display().to_string();
+
+        let data = match read(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                eprintln!("{}: {}", name, err);
+                worst = worst.max(Status::Error);
+                continue;
+            }
+        };
+
+        let status = Status::from_data(&data);
+
+        // only validate reports demo validity in the exit code
+        if matches!(cli.command, Command::Validate(_)) {
+            worst = worst.max(status);
+        }
+
+        match run(&data, status) {
+            Ok(output) => outputs.push((name, output)),
+            Err(err) => {
+                eprintln!("{}: {}", name, err);
+                worst = worst.max(Status::Error);
+            }
+        }
+    }
+
+    render(&outputs, &cli.format);
+
+    ExitCode::from(worst.exit_code())
+}
+
+fn render(outputs: &[(String, Output)], format: &FormatArgs) {
+    let text = if format.json {
+        render_json(outputs)
+    } else if format.csv {
+        render_csv(outputs)
+    } else {
+        render_table(outputs)
+    };
+
+    // ignore broken pipe, e.g. when piped to head
+    let _ = std::io::stdout().lock().write_all(text.as_bytes());
+}
+
+fn index(args: &IndexArgs, format: &FormatArgs) -> ExitCode {
+    let mut paths: Vec<PathBuf> = vec![];
+
+    if let Err(err) = find_demos(&args.dir, &mut paths) {
+        eprintln!("{}: {}", args.dir.display(), err);
+        return ExitCode::from(Status::Error.exit_code());
+    }
+
+    paths.sort();
+    let extractors: Vec<Extractor> = args.extract.iter().map(|e| (*e).into()).collect();
+    let mode = match args.mmap {
+        true => ReadMode::Mmap,
+        false => ReadMode::Read,
+    };
+    let has_errors = AtomicBool::new(false);
+
+    if args.jsonl {
+        let stdout = Mutex::new(std::io::stdout());
+
+        batch_for_each(&paths, &extractors, mode, |item| {
+            if !item.errors.is_empty() {
+                has_errors.store(true, Ordering::Relaxed);
+            }
+
+            let line = format!("{}\n", serde_json::to_string(&item).unwrap_or_default());
+            if let Ok(mut stdout) = stdout.lock() {
+                let _ = stdout.write_all(line.as_bytes());
+            }
+        });
+    } else {
+        let outputs: Vec<(String, Output)> = batch(&paths, &extractors, mode)
+            .into_iter()
+            .map(|item| {
+                if !item.errors.is_empty() {
+                    has_errors.store(true, Ordering::Relaxed);
+                }
+                (item.path.display().to_string(), index_output(&item))
+            })
+            .collect();
+        render(&outputs, format);
+    }
+
+    match has_errors.load(Ordering::Relaxed) {
+        true => ExitCode::from(Status::Error.exit_code()),
+        false => ExitCode::SUCCESS,
+    }
+}
+
+fn find_demos(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_demos(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("mvd"))
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn index_output(item: &BatchItem) -> Output {
+    let summary = item.summary.clone().unwrap_or_default();
+    let status = item.validation.map(|v| match v {
+        v if !v.is_valid && !v.is_truncated => "invalid",
+        v if v.is_truncated => "truncated",
+        v if v.is_aborted => "aborted",
+        _ => "valid",
+    });
+
+    Output {
+        json: to_json(item),
+        headers: vec![
+            "map",
+            "mode",
+            "timestamp",
+            "duration",
+            "players",
+            "status",
+            "errors",
+        ],
+        rows: vec![vec![
+            summary.map.unwrap_or_default(),
+            summary.mode.unwrap_or_default(),
+            summary
+                .timestamp
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            summary
+                .duration
+                .map(|d| seconds(d.as_secs_f64()))
+                .unwrap_or_default(),
+            item.players
+                .as_ref()
+                .map(|p| p.len().to_string())
+                .unwrap_or_default(),
+            status.unwrap_or_default().to_string(),
+            item.errors.join("; "),
+        ]],
+    }
+}
+
+fn expand_paths(patterns: &[String], worst: &mut Status) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = vec![];
+
+    for pattern in patterns {
+        let path = PathBuf::from(pattern);
+
+        // demo names often contain brackets, e.g. "duel_a_vs_b[dm4]20240101-1200.mvd"
+        if path.exists() || !pattern.contains(['*', '?', '[']) {
+            paths.push(path);
+            continue;
+        }
+
+        let matches: Vec<PathBuf> = match glob::glob(pattern) {
+            Ok(entries) => entries.filter_map(Result::ok).collect(),
+            Err(err) => {
+                eprintln!("{}: {}", pattern, err);
+                vec![]
+            }
+        };
+
+        if matches.is_empty() {
+            eprintln!("{}: no matching files", pattern);
+            *worst = (*worst).max(Status::Error);
+        }
+
+        paths.extend(matches);
+    }
+
+    paths
+}
+
+fn info(data: &[u8], status: Status) -> mvdparser::Result<Output> {
+    let settings = serverinfo(data)?;
+    let timestamp = timestamp(data).ok().map(|t| t.to_rfc3339());
+    let duration = match_duration(data).ok().map(|d| d.as_secs_f64());
+    let names = player_names(data).unwrap_or_default();
+
+    Ok(Output {
+        json: json!({
+            "map": settings.map,
+            "mode": settings.mode,
+            "hostname": settings.hostname,
+            "matchtag": settings.matchtag,
+            "timestamp": timestamp,
+            "duration": duration,
+            "players": names,
+            "status": status.name(),
+        }),
+        headers: vec![
+            "map",
+            "mode",
+            "hostname",
+            "matchtag",
+            "timestamp",
+            "duration",
+            "players",
+            "status",
+        ],
+        rows: vec![vec![
+            settings.map.unwrap_or_default(),
+            settings.mode.unwrap_or_default(),
+            unicode::to_utf8(&settings.hostname.unwrap_or_default()),
+            settings.matchtag.unwrap_or_default(),
+            timestamp.unwrap_or_default(),
+            duration.map(seconds).unwrap_or_default(),
+            names
+                .iter()
+                .map(|n| unicode::to_utf8(n))
+                .collect::<Vec<_>>()
+                .join(", "),
+            status.name().to_string(),
+        ]],
+    })
+}
+
+fn players_output(data: &[u8], _: Status) -> mvdparser::Result<Output> {
+    let players = players(data)?;
+
+    Ok(Output {
+        json: to_json(&players),
+        headers: vec!["name", "team", "frags", "ping", "is_bot"],
+        rows: players
+            .iter()
+            .map(|p| {
+                vec![
+                    unicode::to_utf8(&p.name),
+                    unicode::to_utf8(&p.team),
+                    p.frags.to_string(),
+                    p.ping.to_string(),
+                    p.is_bot.to_string(),
+                ]
+            })
+            .collect(),
+    })
+}
+
+fn teams_output(data: &[u8], _: Status) -> mvdparser::Result<Output> {
+    let teams = teams(data)?;
+
+    Ok(Output {
+        json: to_json(&teams),
+        headers: vec!["name", "frags", "ping", "players"],
+        rows: teams
+            .iter()
+            .map(|t| {
+                vec![
+                    unicode::to_utf8(&t.name),
+                    t.frags.to_string(),
+                    t.ping.to_string(),
+                    t.players.len().to_string(),
+                ]
+            })
+            .collect(),
+    })
+}
+
+fn frags(data: &[u8], _: Status) -> mvdparser::Result<Output> {
+    let mut frags: Vec<(String, i32)> = frags_per_player_name(data).into_iter().collect();
+    frags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Ok(Output {
+        json: json!(frags
+            .iter()
+            .map(|(name, frags)| json!({"name": name, "frags": frags}))
+            .collect::<Vec<_>>()),
+        headers: vec!["name", "frags"],
+        rows: frags
+            .iter()
+            .map(|(name, frags)| vec![unicode::to_utf8(name), frags.to_string()])
+            .collect(),
+    })
+}
+
+fn prints_output(data: &[u8], chat_only: bool) -> Output {
+    let prints: Vec<TimedPrint> = timed_prints(data)
+        .into_iter()
+        .filter(|p| !chat_only || p.print.id == PrintId::Chat)
+        .collect();
+
+    Output {
+        json: to_json(&prints),
+        headers: vec!["time", "id", "text"],
+        rows: prints
+            .iter()
+            .map(|p| {
+                vec![
+                    seconds(p.time.as_secs_f64()),
+                    format!("{:?}", p.print.id),
+                    bytestr::to_utf8(&p.print.content).trim_end().to_string(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+fn ktxstats(data: &[u8], _: Status) -> mvdparser::Result<Output> {
+    let stats = ktxstats_v3(data)?;
+
+    Ok(Output {
+        json: to_json(&stats),
+        headers: vec!["name", "team", "frags", "kills", "deaths", "suicides", "tk"],
+        rows: stats
+            .players
+            .iter()
+            .map(|p| {
+                vec![
+                    unicode::to_utf8(&p.name),
+                    unicode::to_utf8(&p.team),
+                    p.stats.frags.to_string(),
+                    p.stats.kills.to_string(),
+                    p.stats.deaths.to_string(),
+                    p.stats.suicides.to_string(),
+                    p.stats.tk.to_string(),
+                ]
+            })
+            .collect(),
+    })
+}
+
+fn validate(status: Status) -> Output {
+    Output {
+        json: json!({ "status": status.name() }),
+        headers: vec!["status"],
+        rows: vec![vec![status.name().to_string()]],
+    }
+}
+
+fn durations(data: &[u8], _: Status) -> mvdparser::Result<Output> {
+    let secs = |d: mvdparser::Result<Duration>| d.ok().map(|d| d.as_secs_f64());
+    let countdown = secs(countdown_duration(data));
+    let duration = match_duration_info(data).ok();
+    let game = duration.as_ref().map(|d| d.game.as_secs_f64());
+    let demo = secs(demo_duration(data));
+    let wall_clock = duration.as_ref().map(|d| d.wall_clock.as_secs_f64());
+    let values = [countdown, game, demo, wall_clock];
+
+    Ok(Output {
+        json: json!({
+            "countdown": countdown,
+            "match": game,
+            "demo": demo,
+            "wall_clock": wall_clock,
+        }),
+        headers: vec!["countdown", "match", "demo", "wall_clock"],
+        rows: vec![values
+            .iter()
+            .map(|v| v.map(seconds).unwrap_or_default())
+            .collect()],
+    })
+}
+
+fn seconds(value: f64) -> String {
+    format!("{:.3}", value)
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn with_file_column(outputs: &[(String, Output)]) -> (Vec<&'static str>, Vec<Vec<String>>) {
+    let show_file = outputs.len() > 1;
+    let mut headers: Vec<&'static str> = vec![];
+    let mut rows: Vec<Vec<String>> = vec![];
+
+    for (file, output) in outputs {
+        if headers.is_empty() {
+            headers = match show_file {
+                true => [vec!["file"], output.headers.clone()].concat(),
+                false => output.headers.clone(),
+            };
+        }
+
+        for row in &output.rows {
+            rows.push(match show_file {
+                true => [vec![file.clone()], row.clone()].concat(),
+                false => row.clone(),
+            });
+        }
+    }
+
+    (headers, rows)
+}
+
+fn render_json(outputs: &[(String, Output)]) -> String {
+    let value: Vec<Value> = outputs
+        .iter()
+        .map(|(file, output)| json!({ "file": file, "data": output.json }))
+        .collect();
+    format!(
+        "{}\n",
+        serde_json::to_string_pretty(&value).unwrap_or_default()
+    )
+}
+
+fn render_csv(outputs: &[(String, Output)]) -> String {
+    let (headers, rows) = with_file_column(outputs);
+
+    if headers.is_empty() {
+        return String::new();
+    }
+
+    let mut text = format!("{}\n", headers.join(","));
+
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        text.push_str(&format!("{}\n", fields.join(",")));
+    }
+
+    text
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_table(outputs: &[(String, Output)]) -> String {
+    let (headers, rows) = with_file_column(outputs);
+
+    if headers.is_empty() {
+        return String::new();
+    }
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+
+    let line = |fields: Vec<&str>| -> String {
+        fields
+            .iter()
+            .zip(&widths)
+            .map(|(f, w)| format!("{:<w$}", f, w = w))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let separators: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let mut text = format!("{}\n", line(headers.clone()));
+    text.push_str(&format!(
+        "{}\n",
+        line(separators.iter().map(|s| s.as_str()).collect())
+    ));
+
+    for row in &rows {
+        text.push_str(&format!(
+            "{}\n",
+            line(row.iter().map(|f| f.as_str()).collect())
+        ));
+    }
+
+    text
+}
@@ -11,6 +11,21 @@ pub fn offsets_between(data: &[u8], a: &[u8], b: &[u8]) -> Option<(usize, usize)
     }
 }
 
+// quake text as unicode string (see quake_text::bytestr::to_unicode)
+#[cfg(feature = "serde")]
+pub mod unicode_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&quake_text::bytestr::to_unicode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(quake_text::unicode::to_bytestr(&value))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
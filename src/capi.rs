@@ -0,0 +1,359 @@
+// C interface, see include header generated by cbindgen (OUT_DIR/mvdparser.h)
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::ptr::{null_mut, slice_from_raw_parts_mut};
+use std::slice;
+
+use crate::error::Result;
+use crate::player::Player;
+use crate::team::Team;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+// value on success, null (and last error set) on failure
+fn ok_or_null<T>(result: Result<T>) -> *mut T {
+    match result {
+        Ok(value) => Box::into_raw(Box::new(value)),
+        Err(err) => {
+            set_last_error(&err.to_string());
+            null_mut()
+        }
+    }
+}
+
+fn to_c_string(value: &str) -> *mut c_char {
+    CString::new(value.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+unsafe fn free_c_string(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Opaque handle to a demo, created with mvd_open and freed with mvd_close.
+pub struct MvdDemo {
+    data: Vec<u8>,
+}
+
+#[repr(C)]
+pub struct MvdPlayer {
+    pub name: *mut c_char,
+    pub team: *mut c_char,
+    pub color: [u8; 2],
+    pub frags: i32,
+    pub ping: u32,
+    pub is_bot: bool,
+}
+
+#[repr(C)]
+pub struct MvdPlayerList {
+    pub items: *mut MvdPlayer,
+    pub len: usize,
+}
+
+#[repr(C)]
+pub struct MvdTeam {
+    pub name: *mut c_char,
+    pub color: [u8; 2],
+    pub frags: i32,
+    pub ping: u32,
+    pub players: MvdPlayerList,
+}
+
+#[repr(C)]
+pub struct MvdTeamList {
+    pub items: *mut MvdTeam,
+    pub len: usize,
+}
+
+impl From<&Player> for MvdPlayer {
+    fn from(player: &Player) -> Self {
+        Self {
+            name: to_c_string(&player.name),
+            team: to_c_string(&player.team),
+            color: player.color,
+            frags: player.frags,
+            ping: player.ping,
+            is_bot: player.is_bot,
+        }
+    }
+}
+
+impl From<&[Player]> for MvdPlayerList {
+    fn from(players: &[Player]) -> Self {
+        let items: Box<[MvdPlayer]> = players.iter().map(MvdPlayer::from).collect();
+        let len = items.len();
+        Self {
+            items: Box::into_raw(items) as *mut MvdPlayer,
+            len,
+        }
+    }
+}
+
+impl From<&Team> for MvdTeam {
+    fn from(team: &Team) -> Self {
+        Self {
+            name: to_c_string(&team.name),
+            color: team.color,
+            frags: team.frags,
+            ping: team.ping,
+            players: MvdPlayerList::from(team.players.as_slice()),
+        }
+    }
+}
+
+impl From<&[Team]> for MvdTeamList {
+    fn from(teams: &[Team]) -> Self {
+        let items: Box<[MvdTeam]> = teams.iter().map(MvdTeam::from).collect();
+        let len = items.len();
+        Self {
+            items: Box::into_raw(items) as *mut MvdTeam,
+            len,
+        }
+    }
+}
+
+unsafe fn free_player_items(list: &MvdPlayerList) {
+    let items = Box::from_raw(slice_from_raw_parts_mut(list.items, list.len));
+    for player in items.iter() {
+        free_c_string(player.name);
+        free_c_string(player.team);
+    }
+}
+
+/// Last error message of the current thread, or null. Owned by the library, do not free.
+/// The pointer is invalidated by the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn mvd_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |m| m.as_ptr()))
+}
+
+/// Copies len bytes of demo data. Returns null if data is null.
+///
+/// # Safety
+/// data must point to len readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_open(data: *const u8, len: usize) -> *mut MvdDemo {
+    if data.is_null() {
+        set_last_error("data is null");
+        return null_mut();
+    }
+
+    let data = slice::from_raw_parts(data, len).to_vec();
+    Box::into_raw(Box::new(MvdDemo { data }))
+}
+
+/// # Safety
+/// demo must be null or returned by mvd_open, and not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_close(demo: *mut MvdDemo) {
+    if !demo.is_null() {
+        drop(Box::from_raw(demo));
+    }
+}
+
+unsafe fn demo_data<'a>(demo: *const MvdDemo) -> Option<&'a [u8]> {
+    match demo.as_ref() {
+        Some(demo) => Some(&demo.data),
+        None => {
+            set_last_error("demo is null");
+            None
+        }
+    }
+}
+
+// value, or null with the last error set (also if the field is missing)
+unsafe fn string_field(
+    demo: *const MvdDemo,
+    name: &str,
+    field: fn(&[u8]) -> Result<Option<String>>,
+) -> *mut c_char {
+    let Some(data) = demo_data(demo) else {
+        return null_mut();
+    };
+
+    match field(data) {
+        Ok(Some(value)) => to_c_string(&value),
+        Ok(None) => {
+            set_last_error(&format!("{} not found", name));
+            null_mut()
+        }
+        Err(err) => {
+            set_last_error(&err.to_string());
+            null_mut()
+        }
+    }
+}
+
+/// Map name, or null if not found. Free with mvd_string_free.
+///
+/// # Safety
+/// demo must be null or returned by mvd_open.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_map(demo: *const MvdDemo) -> *mut c_char {
+    string_field(demo, "map", |data| {
+        crate::serverinfo::serverinfo(data).map(|s| s.map)
+    })
+}
+
+/// Game mode (e.g. "1on1" or "4on4"), or null if not found. Free with mvd_string_free.
+///
+/// # Safety
+/// demo must be null or returned by mvd_open.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_mode(demo: *const MvdDemo) -> *mut c_char {
+    string_field(demo, "mode", |data| {
+        crate::serverinfo::serverinfo(data).map(|s| s.mode)
+    })
+}
+
+/// # Safety
+/// value must be null or returned by this library, and not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_string_free(value: *mut c_char) {
+    free_c_string(value);
+}
+
+/// Match duration in milliseconds, or -1 if not found.
+///
+/// # Safety
+/// demo must be null or returned by mvd_open.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_match_duration_ms(demo: *const MvdDemo) -> i64 {
+    let Some(data) = demo_data(demo) else {
+        return -1;
+    };
+
+    match crate::duration::match_duration(data) {
+        Ok(duration) => duration.as_millis() as i64,
+        Err(err) => {
+            set_last_error(&err.to_string());
+            -1
+        }
+    }
+}
+
+/// Players, or null if not found. Free with mvd_players_free.
+///
+/// # Safety
+/// demo must be null or returned by mvd_open.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_players(demo: *const MvdDemo) -> *mut MvdPlayerList {
+    let Some(data) = demo_data(demo) else {
+        return null_mut();
+    };
+    ok_or_null(crate::players::players(data).map(|p| MvdPlayerList::from(p.as_slice())))
+}
+
+/// # Safety
+/// players must be null or returned by mvd_players, and not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_players_free(players: *mut MvdPlayerList) {
+    if !players.is_null() {
+        let players = Box::from_raw(players);
+        free_player_items(&players);
+    }
+}
+
+/// Teams, or null if not found. Free with mvd_teams_free.
+///
+/// # Safety
+/// demo must be null or returned by mvd_open.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_teams(demo: *const MvdDemo) -> *mut MvdTeamList {
+    let Some(data) = demo_data(demo) else {
+        return null_mut();
+    };
+    ok_or_null(crate::teams::teams(data).map(|t| MvdTeamList::from(t.as_slice())))
+}
+
+/// # Safety
+/// teams must be null or returned by mvd_teams, and not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mvd_teams_free(teams: *mut MvdTeamList) {
+    if teams.is_null() {
+        return;
+    }
+
+    let teams = Box::from_raw(teams);
+    let items = Box::from_raw(slice_from_raw_parts_mut(teams.items, teams.len));
+
+    for team in items.iter() {
+        free_c_string(team.name);
+        free_player_items(&team.players);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testdemo::to_all;
+
+    #[test]
+    fn test_capi() -> Result<()> {
+        let data = read("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")?;
+
+        unsafe {
+            let demo = mvd_open(data.as_ptr(), data.len());
+            assert!(!demo.is_null());
+
+            let map = mvd_map(demo);
+            assert_eq!(CStr::from_ptr(map).to_str()?, "povdmm4");
+            mvd_string_free(map);
+            assert_eq!(mvd_match_duration_ms(demo), 180_000);
+
+            let players = mvd_players(demo);
+            assert_eq!((*players).len, 2);
+            let equ = &*(*players).items.add(1);
+            assert_eq!(CStr::from_ptr(equ.name).to_str()?, "eQu");
+            assert_eq!(equ.frags, 19);
+            mvd_players_free(players);
+
+            let teams = mvd_teams(demo);
+            assert_eq!((*teams).len, 2);
+            assert_eq!((*(*teams).items).players.len, 1);
+            mvd_teams_free(teams);
+
+            mvd_close(demo);
+        }
+
+        unsafe {
+            let demo = mvd_open(b"".as_ptr(), 0);
+            assert!(mvd_players(demo).is_null());
+            assert_eq!(
+                CStr::from_ptr(mvd_last_error()).to_str()?,
+                "Unable to find clientinfo strings"
+            );
+            mvd_close(demo);
+
+            // missing field after another error
+            let data = to_all(0, b"\x09fullserverinfo \"\\mode\\1on1\"\n\0");
+            let demo = mvd_open(data.as_ptr(), data.len());
+            assert!(mvd_map(demo).is_null());
+            assert_eq!(CStr::from_ptr(mvd_last_error()).to_str()?, "map not found");
+            mvd_close(demo);
+
+            assert!(mvd_open(std::ptr::null(), 0).is_null());
+            assert!(mvd_map(std::ptr::null()).is_null());
+            assert_eq!(mvd_match_duration_ms(std::ptr::null()), -1);
+        }
+
+        Ok(())
+    }
+}
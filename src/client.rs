@@ -1,6 +1,7 @@
 use quake_clientinfo::Clientinfo;
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Client {
     pub number: u8,
     pub name: String,
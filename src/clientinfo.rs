@@ -1,4 +1,4 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 use bstr::ByteSlice;
 pub use quake_clientinfo::Clientinfo;
 
@@ -14,7 +14,7 @@ pub fn clientinfo_strings(data: &[u8]) -> Result<Vec<String>> {
     const CMD_SPAWN: [u8; 0x0A] = [0x09, 0x63, 0x6D, 0x64, 0x20, 0x73, 0x70, 0x61, 0x77, 0x6E];
 
     let Some(mut offset) = data.find(CMD_SPAWN) else {
-        return Err(e!("Unable to find clientinfo strings"));
+        return Err(Error::MissingClientinfo);
     };
     const MAX_PLAYERS: usize = 24;
     const MAX_LOOKAHEAD: usize = 512;
@@ -24,7 +24,7 @@ pub fn clientinfo_strings(data: &[u8]) -> Result<Vec<String>> {
 
     let mut result: Vec<String> = vec![];
 
-    while let Some(name_offset) = data[offset..offset + MAX_LOOKAHEAD]
+    while let Some(name_offset) = data[offset..data.len().min(offset + MAX_LOOKAHEAD)]
         .find(br#"\name\"#)
         .map(|o| offset + o)
     {
@@ -60,6 +60,15 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_clientinfo_strings_truncated() -> Result<()> {
+        assert_eq!(
+            clientinfo_strings(b"\x09cmd spawn\\name\\")?,
+            Vec::<String>::new()
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_clientinfo() -> Result<()> {
         assert_eq!(
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 use bstr::ByteSlice;
 use quake_text::unicode;
 
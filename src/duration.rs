@@ -1,18 +1,21 @@
 use std::time::Duration;
 
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 use bstr::ByteSlice;
 
+use crate::hoonymode::is_hoonymode;
+use crate::overtime::overtime;
+use crate::paused::{pauses, Pause};
 use crate::qw::frame;
-use crate::{bytesextra, ktxstats_string, matchdate, serverinfo};
+use crate::{bytesextra, ktxstats_string, matchdate};
 
 pub fn countdown_duration(data: &[u8]) -> Result<Duration> {
-    if serverinfo(data).is_ok_and(|s| s.mode == Some("hoonymode".to_string())) {
+    if is_hoonymode(data) {
         return Ok(Duration::ZERO);
     }
 
     let Some(offset) = data.find(matchdate::MATCHDATE_NEEDLE) else {
-        return Err(e!("Countdown not found"));
+        return Err(Error::MissingCountdown);
     };
     Ok(duration_until_offset(data, offset))
 }
@@ -32,8 +35,37 @@ pub fn demo_duration(data: &[u8]) -> Result<Duration> {
     Ok(duration_until_offset(data, offset))
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchDuration {
+    pub game: Duration,
+    // game time plus the real time of pauses with paused duration messages (QTV),
+    // a lower bound if unknown_pauses > 0
+    pub wall_clock: Duration,
+    pub unknown_pauses: usize, // pauses without paused duration messages, e.g. server side demos
+    pub overtime: Option<Duration>, // included in game time
+}
+
+// game time and pauses
+pub fn match_duration_info(data: &[u8]) -> Result<MatchDuration> {
+    let game = match_duration(data)?;
+    let match_start = data.find(matchdate::MATCHDATE_NEEDLE).unwrap_or(0);
+    let pauses: Vec<Pause> = pauses(data)
+        .into_iter()
+        .filter(|p| p.index >= match_start)
+        .collect();
+    let paused: Duration = pauses.iter().filter_map(|p| p.real_duration).sum();
+
+    Ok(MatchDuration {
+        game,
+        wall_clock: game + paused,
+        unknown_pauses: pauses.iter().filter(|p| p.real_duration.is_none()).count(),
+        overtime: overtime(data).and_then(|o| o.duration),
+    })
+}
+
 pub fn match_duration(data: &[u8]) -> Result<Duration> {
-    if serverinfo(data).is_ok_and(|s| s.mode == Some("hoonymode".to_string())) {
+    if is_hoonymode(data) {
         return match_duration_from_seeking(data);
     }
 
@@ -43,7 +75,7 @@ pub fn match_duration(data: &[u8]) -> Result<Duration> {
 pub fn match_duration_from_seeking(data: &[u8]) -> Result<Duration> {
     let end = demo_duration(data)?;
     let begin = countdown_duration(data).unwrap_or(Duration::ZERO);
-    Ok(end - begin)
+    Ok(end.saturating_sub(begin))
 }
 
 pub fn match_duration_from_ktxstats(data: &[u8]) -> Result<Duration> {
@@ -51,9 +83,11 @@ pub fn match_duration_from_ktxstats(data: &[u8]) -> Result<Duration> {
     let Some((from, to)) =
         bytesextra::offsets_between(ktxstats_s.as_bytes(), br#""duration": "#, b",")
     else {
-        return Err(e!("Duration not found in ktxstats"));
+        return Err(Error::InvalidKtxstats("duration not found".to_string()));
     };
-    let duration_f: f64 = ktxstats_s[from..to].parse()?;
+    let duration_f: f64 = ktxstats_s[from..to]
+        .parse()
+        .map_err(|_| Error::InvalidKtxstats("invalid duration".to_string()))?;
     Ok(Duration::from_secs_f64(duration_f))
 }
 
@@ -81,6 +115,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::testdemo::{hidden, print, to_all};
 
     #[test]
     fn test_countdown_duration() -> Result<()> {
@@ -210,4 +245,64 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_match_duration_wall_clock() -> Result<()> {
+        {
+            let demo_data = read("tests/files/ffa_5[dm4]20240501-1229.mvd")?;
+            let result = match_duration_info(&demo_data)?;
+            assert_eq!(result.game.as_secs(), 61);
+            assert_eq!(result.wall_clock, result.game);
+            assert_eq!(result.unknown_pauses, 0);
+            assert_eq!(result.overtime, None);
+        }
+        {
+            // server side demo, pause without paused duration messages
+            let demo_data = read("tests/files/4on4_-s-_vs_pol[dm2]20241118-2135.mvd")?;
+            let result = match_duration_info(&demo_data)?;
+            assert_eq!(result.wall_clock, result.game);
+            assert_eq!(result.unknown_pauses, 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_duration_paused_duration_messages() -> Result<()> {
+        let paused_ms = |msec: u8| [1, 0, 0, 0, 0x0A, 0, msec];
+
+        // 2s game time, paused twice: 150ms known (qtv) and once without duration messages
+        let data = [
+            to_all(100, &print(2, b"matchdate: 2024-05-01 12:29:00 CEST\n")),
+            to_all(0, &[24, 1]), // svc_setpause on
+            hidden(&[paused_ms(100), paused_ms(50)].concat()),
+            to_all(0, &[24, 0]),
+            to_all(250, &[]),
+            to_all(0, &[24, 1]),
+            to_all(250, &[]),
+            to_all(0, &print(2, b"The match is over\n")),
+        ]
+        .concat();
+
+        let result = match_duration_info(&data)?;
+        assert_eq!(result.game.as_millis(), 500);
+        assert_eq!(result.wall_clock.as_millis(), 650);
+        assert_eq!(result.unknown_pauses, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_duration_from_seeking_end_before_start() -> Result<()> {
+        fn frame(duration: u8, body: &[u8]) -> Vec<u8> {
+            let size = (body.len() as u32).to_le_bytes();
+            [&[duration, 6], size.as_slice(), body].concat()
+        }
+
+        let data = [
+            frame(10, b"The match is over"),
+            frame(50, &matchdate::MATCHDATE_NEEDLE),
+        ]
+        .concat();
+        assert_eq!(match_duration_from_seeking(&data)?, Duration::ZERO);
+        Ok(())
+    }
 }
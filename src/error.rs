@@ -0,0 +1,81 @@
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    MissingServerinfo,
+    MissingClientinfo,
+    MissingMatchdate,
+    MissingCountdown,
+    MissingEpoch,
+    MissingFilename,
+    MissingPings,
+    NoKtxstats,
+    InvalidKtxstats(String),
+    InvalidMatchdate,
+    InvalidTimezone,
+    InvalidTimestamp,
+    InvalidBlock,
+    TruncatedFrame {
+        offset: usize,
+        expected: usize,
+        available: usize,
+    },
+    UnknownPrint(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::MissingServerinfo => write!(f, "Serverinfo not found"),
+            Error::MissingClientinfo => write!(f, "Unable to find clientinfo strings"),
+            Error::MissingMatchdate => write!(f, "Matchdate not found"),
+            Error::MissingCountdown => write!(f, "Countdown not found"),
+            Error::MissingEpoch => write!(f, "Epoch not found in serverinfo"),
+            Error::MissingFilename => write!(f, "Filename not found"),
+            Error::MissingPings => write!(f, "Unable to read pings"),
+            Error::NoKtxstats => write!(f, "ktxstats not found"),
+            Error::InvalidKtxstats(err) => write!(f, "Invalid ktxstats: {}", err),
+            Error::InvalidMatchdate => write!(f, "Invalid matchdate"),
+            Error::InvalidTimezone => write!(f, "Invalid timezone abbreviation"),
+            Error::InvalidTimestamp => write!(f, "Unable to parse timestamp from epoch"),
+            Error::InvalidBlock => write!(f, "Invalid demoinfo block"),
+            Error::TruncatedFrame {
+                offset,
+                expected,
+                available,
+            } => write!(
+                f,
+                "Frame at offset {} is smaller than expected size ({} of {} bytes)",
+                offset, available, expected
+            ),
+            Error::UnknownPrint(value) => write!(f, r#"Unable to parse message: "{}""#, value),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Error::MissingServerinfo.to_string(), "Serverinfo not found");
+        assert_eq!(
+            Error::TruncatedFrame {
+                offset: 4,
+                expected: 10,
+                available: 6,
+            }
+            .to_string(),
+            "Frame at offset 4 is smaller than expected size (6 of 10 bytes)"
+        );
+    }
+}
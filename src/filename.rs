@@ -1,10 +1,10 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 
 use crate::serverinfo;
 
 pub fn filename(data: &[u8]) -> Result<String> {
     let Some(serverdemo) = serverinfo(data)?.serverdemo else {
-        return Err(e!("Filename not found"));
+        return Err(Error::MissingFilename);
     };
     Ok(serverdemo)
 }
@@ -20,14 +20,12 @@ mod tests {
 
     #[test]
     fn test_filename() -> Result<()> {
-        assert_eq!(
+        assert!(matches!(
             filename(&read(
                 "tests/files/ctf_blue_vs_red[ctf5]20240520-1925.mvd.ktxstats.json"
-            )?)
-            .unwrap_err()
-            .to_string(),
-            "Serverinfo not found".to_string()
-        );
+            )?),
+            Err(Error::MissingServerinfo)
+        ));
 
         assert_eq!(
             filename(&read(
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use bstr::ByteSlice;
+
+use crate::clients::player_clients;
+use crate::error::Result;
+use crate::matchdate::{matchdate_string, MATCHDATE_NEEDLE};
+use crate::scoreboard::scoreboard_timeline;
+use crate::serverinfo::serverinfo;
+
+// stable identity of a match, the same in server and qtv recordings of a game
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MatchIdentity {
+    settings: Vec<(&'static str, String)>,
+    players: Vec<(String, String)>, // name and team, sorted
+    matchdate: Option<String>,
+}
+
+impl MatchIdentity {
+    fn from_data(data: &[u8]) -> Result<Self> {
+        let settings = serverinfo(data)?;
+
+        let mut players: Vec<(String, String)> = player_clients(data)?
+            .into_iter()
+            .map(|c| (c.name, c.team))
+            .collect();
+        players.sort();
+
+        Ok(Self {
+            settings: vec![
+                ("map", settings.map.unwrap_or_default()),
+                ("mode", settings.mode.unwrap_or_default()),
+                ("deathmatch", option_string(settings.deathmatch)),
+                ("teamplay", option_string(settings.teamplay)),
+                ("timelimit", option_string(settings.timelimit)),
+                ("fraglimit", option_string(settings.fraglimit)),
+            ],
+            players,
+            matchdate: matchdate_string(data).ok(),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        let mut push = |value: &str| {
+            bytes.extend_from_slice(value.as_bytes());
+            bytes.push(0);
+        };
+
+        for (key, value) in &self.settings {
+            push(key);
+            push(value);
+        }
+        for (name, team) in &self.players {
+            push(name);
+            push(team);
+        }
+        push(self.matchdate.as_deref().unwrap_or_default());
+
+        bytes
+    }
+
+    // matchdate is only printed at match start, recordings that joined later do not have it
+    fn is_same(&self, other: &Self) -> bool {
+        let is_same_matchdate = match (&self.matchdate, &other.matchdate) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+
+        self.settings == other.settings && self.players == other.players && is_same_matchdate
+    }
+}
+
+// frags per player name after match start
+fn frag_timeline(data: &[u8]) -> Result<BTreeMap<String, Vec<i32>>> {
+    let clients = player_clients(data)?;
+    let match_start = data.find(MATCHDATE_NEEDLE).unwrap_or_default();
+    let mut frags: BTreeMap<String, Vec<i32>> = BTreeMap::new();
+
+    for update in scoreboard_timeline(data)
+        .updates
+        .into_iter()
+        .filter(|u| u.index >= match_start)
+    {
+        let Some(client) = clients.iter().find(|c| c.number == update.number) else {
+            continue;
+        };
+        let values = frags.entry(client.name.clone()).or_default();
+
+        if values.last() != Some(&update.frags) {
+            values.push(update.frags);
+        }
+    }
+
+    Ok(frags)
+}
+
+// frags of one can be a part of those of the other, e.g. qtv joined late or stopped early
+fn has_same_frags(a: &BTreeMap<String, Vec<i32>>, b: &BTreeMap<String, Vec<i32>>) -> bool {
+    a.iter().all(|(name, values)| {
+        let other_values = b.get(name).cloned().unwrap_or_default();
+        let (short, long) = match values.len() <= other_values.len() {
+            true => (values.as_slice(), other_values.as_slice()),
+            false => (other_values.as_slice(), values.as_slice()),
+        };
+        short.is_empty() || long.windows(short.len()).any(|w| w == short)
+    })
+}
+
+// stable hash of serverinfo, players and matchdate, independent of frame layout and file name
+pub fn fingerprint(data: &[u8]) -> Result<String> {
+    let identity = MatchIdentity::from_data(data)?;
+    Ok(format!("{:016x}", fnv1a(&identity.to_bytes())))
+}
+
+pub fn same_match(a: &[u8], b: &[u8]) -> bool {
+    let (Ok(a_identity), Ok(b_identity)) =
+        (MatchIdentity::from_data(a), MatchIdentity::from_data(b))
+    else {
+        return false;
+    };
+
+    if !a_identity.is_same(&b_identity) {
+        return false;
+    }
+
+    let (Ok(a_frags), Ok(b_frags)) = (frag_timeline(a), frag_timeline(b)) else {
+        return false;
+    };
+
+    has_same_frags(&a_frags, &b_frags) && has_same_frags(&b_frags, &a_frags)
+}
+
+fn option_string(value: Option<i32>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::recovery::repair;
+    use crate::testdemo::{print, to_all};
+
+    use super::*;
+
+    const DUEL_PATH: &str = "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd";
+    const OTHER_PATH: &str = "tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd";
+
+    #[test]
+    fn test_fingerprint() -> Result<()> {
+        let data = read(DUEL_PATH)?;
+        let other = read(OTHER_PATH)?;
+
+        assert_eq!(fingerprint(&data)?, fingerprint(&data.clone())?);
+        assert_eq!(fingerprint(&data)?, "c21ecfbd9e08b2d8");
+        assert_ne!(fingerprint(&data)?, fingerprint(&other)?);
+        assert!(fingerprint(&[]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_match() -> Result<()> {
+        let data = read(DUEL_PATH)?;
+        let other = read(OTHER_PATH)?;
+
+        assert!(same_match(&data, &data));
+        assert!(!same_match(&data, &other));
+        assert!(!same_match(&data, &[]));
+
+        // a recording that stopped early
+        let partial = repair(&data[..data.len() * 3 / 4]);
+        assert_eq!(fingerprint(&data)?, fingerprint(&partial)?);
+        assert!(same_match(&data, &partial));
+        assert!(same_match(&partial, &data));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_match_server_and_qtv() {
+        let stufftext = |text: &[u8]| [&[9], text, &[0]].concat();
+        let userinfo = |slot: u8, info: &[u8]| [&[0x28, slot, 1, 0, 0, 0], info, &[0]].concat();
+        let serverinfo = |epoch: &[u8]| {
+            stufftext(
+                &[
+                    b"fullserverinfo \"\\map\\dm2\\deathmatch\\3\\*epoch\\".as_slice(),
+                    epoch,
+                    b"\"\n",
+                ]
+                .concat(),
+            )
+        };
+        let clients = [
+            stufftext(b"cmd spawn\n"),
+            userinfo(0, b"\\name\\FOO\\team\\red"),
+            userinfo(1, b"\\name\\BAR\\team\\blue"),
+        ]
+        .concat();
+        let matchdate = |date: &[u8]| print(2, &[b"matchdate: ", date, b" CET\n"].concat());
+        let frags = |slot: u8, frags: u8| vec![14, slot, frags, 0];
+
+        // server records one message per frame
+        let server = [
+            to_all(0, &serverinfo(b"1704106800")),
+            to_all(0, &clients),
+            to_all(100, &matchdate(b"2024-01-01 12:00:00")),
+            to_all(100, &frags(0, 1)),
+            to_all(100, &frags(1, 1)),
+            to_all(100, &frags(0, 2)),
+            to_all(100, &frags(1, 2)),
+        ]
+        .concat();
+
+        // qtv connects later, groups messages and stops before the last frag
+        let qtv = [
+            to_all(0, &[serverinfo(b"1704106830"), clients.clone()].concat()),
+            to_all(
+                200,
+                &[matchdate(b"2024-01-01 12:00:00"), frags(0, 1), frags(1, 1)].concat(),
+            ),
+            to_all(150, &frags(0, 2)),
+        ]
+        .concat();
+
+        assert_eq!(fingerprint(&server).unwrap(), fingerprint(&qtv).unwrap());
+        assert!(same_match(&server, &qtv));
+        assert!(same_match(&qtv, &server));
+
+        // rematch with the same players
+        let rematch = [
+            to_all(0, &serverinfo(b"1704107800")),
+            to_all(0, &clients),
+            to_all(100, &matchdate(b"2024-01-01 12:15:00")),
+            to_all(100, &frags(0, 1)),
+        ]
+        .concat();
+        assert_ne!(
+            fingerprint(&server).unwrap(),
+            fingerprint(&rematch).unwrap()
+        );
+        assert!(!same_match(&server, &rematch));
+
+        // same identity, other frags
+        let other_frags = [
+            to_all(0, &serverinfo(b"1704106800")),
+            to_all(0, &clients),
+            to_all(100, &matchdate(b"2024-01-01 12:00:00")),
+            to_all(100, &frags(0, 1)),
+            to_all(100, &frags(1, 1)),
+            to_all(100, &frags(0, 3)),
+        ]
+        .concat();
+        assert!(!same_match(&server, &other_frags));
+    }
+
+    #[test]
+    fn test_same_match_late_join() {
+        let stufftext = |text: &[u8]| [&[9], text, &[0]].concat();
+        let userinfo = |slot: u8, info: &[u8]| [&[0x28, slot, 1, 0, 0, 0], info, &[0]].concat();
+        let init = [
+            stufftext(b"fullserverinfo \"\\map\\dm2\\deathmatch\\3\"\n"),
+            stufftext(b"cmd spawn\n"),
+            userinfo(0, b"\\name\\FOO\\team\\red"),
+            userinfo(1, b"\\name\\BAR\\team\\blue"),
+        ]
+        .concat();
+        let frags = |slot: u8, frags: u8| vec![14, slot, frags, 0];
+
+        let server = [
+            to_all(0, &init),
+            to_all(100, &print(2, b"matchdate: 2024-01-01 12:00:00 CET\n")),
+            to_all(100, &frags(0, 1)),
+            to_all(100, &frags(1, 1)),
+            to_all(100, &frags(0, 2)),
+        ]
+        .concat();
+
+        // qtv joined after match start and never saw the matchdate print
+        let qtv = [
+            to_all(0, &init),
+            to_all(100, &frags(1, 1)),
+            to_all(100, &frags(0, 2)),
+        ]
+        .concat();
+
+        assert!(same_match(&server, &qtv));
+        assert!(same_match(&qtv, &server));
+
+        let other_players = [
+            to_all(0, &init.replace(b"BAR", b"BAZ")),
+            to_all(100, &frags(1, 1)),
+        ]
+        .concat();
+        assert!(!same_match(&server, &other_players));
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -1,110 +1,442 @@
 use std::collections::HashMap;
-use std::io::Cursor;
+use std::time::Duration;
 
 use bstr::ByteSlice;
+use quake_text::bytestr::to_ascii;
 
-use crate::qw::flagevent::FlagEvent;
-use crate::qw::flagprint;
-use crate::qw::frame;
-use crate::qw::message::message_type::ReadMessageType;
-use crate::qw::message::print::ReadPrint;
-use crate::qw::prot::{MessageType, PrintId};
+use crate::duration::demo_duration;
+
+pub use crate::qw::flagevent::{FlagColor, FlagEvent};
+use crate::qw::fragevent::FragEvent;
+use crate::qw::message::{messages, Message};
+use crate::qw::prot::{PrintId, Target};
+use crate::qw::{flagprint, frame, runeprint};
+use crate::userinfo::name_timeline;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rune {
+    Resistance,
+    Strength,
+    Haste,
+    Regeneration,
+}
+
+impl Rune {
+    // same order as the rune prints
+    pub const ALL: [Rune; 4] = [
+        Rune::Resistance,
+        Rune::Strength,
+        Rune::Haste,
+        Rune::Regeneration,
+    ];
+
+    pub fn from_pickup_print(content: &[u8]) -> Option<Self> {
+        let text = to_ascii(content.trim()).to_lowercase();
+        runeprint::YOU_GOT_RUNE
+            .iter()
+            .position(|p| text.ends_with(&p.to_lowercase()))
+            .map(|i| Self::ALL[i])
+    }
+}
+
+// a rune picked up by a player
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunePickup {
+    pub index: usize,
+    pub time: Duration,
+    pub player: String,
+    pub rune: Rune,
+}
+
+// a rune held by a player, from pickup until death
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuneRun {
+    pub player: String,
+    pub rune: Rune,
+    pub from: Duration,
+    pub to: Duration,
+    pub frags: u32,
+}
+
+impl RuneRun {
+    pub fn duration(&self) -> Duration {
+        self.to.saturating_sub(self.from)
+    }
+}
 
 #[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerFlagEvents {
-    captures: u8,
-    pickups: u8,
-    returns: u8,
-    carrier_frags: u8,
-    defends: u8,
-    carrier_defends: u8,
-    carrier_defends_vs_aggressive: u8,
+    pub captures: u8,
+    pub pickups: u8,
+    pub drops: u8,
+    pub returns: u8,
+    pub carrier_frags: u8,
+    pub defends: u8,
+    pub carrier_defends: u8,
+    pub carrier_defends_vs_aggressive: u8,
 }
 
-pub fn flag_events_per_player_name(data: &[u8]) -> HashMap<String, PlayerFlagEvents> {
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CtfEvent {
+    pub index: usize,
+    pub time: Duration,
+    pub event: FlagEvent,
+    pub flag: Option<FlagColor>,
+}
+
+// a flag carried by a player, from pickup until captured or lost
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlagRun {
+    pub player: String,
+    pub flag: FlagColor,
+    pub from: Duration,
+    pub to: Duration,
+    pub is_captured: bool,
+}
+
+impl FlagRun {
+    pub fn duration(&self) -> Duration {
+        self.to.saturating_sub(self.from)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CtfStats {
+    pub events: Vec<CtfEvent>,
+    pub captures: HashMap<FlagColor, u32>, // per capturing team
+    pub runs: Vec<FlagRun>,
+    pub rune_pickups: Vec<RunePickup>,
+    pub rune_runs: Vec<RuneRun>,
+}
+
+impl CtfStats {
+    pub fn from_events(events: Vec<CtfEvent>, end: Duration) -> Self {
+        let mut captures: HashMap<FlagColor, u32> = HashMap::new();
+        let mut runs: Vec<FlagRun> = vec![];
+        let mut carriers: HashMap<FlagColor, (String, Duration)> = HashMap::new();
+
+        for e in events.iter() {
+            let Some(flag) = e.flag else {
+                continue;
+            };
+
+            match &e.event {
+                FlagEvent::GotFlag { player } => {
+                    carriers.insert(flag, (player.clone(), e.time));
+                }
+                FlagEvent::CapturedFlag { .. } | FlagEvent::LostFlag { .. } => {
+                    let is_captured = matches!(e.event, FlagEvent::CapturedFlag { .. });
+
+                    if is_captured {
+                        *captures.entry(flag.opposite()).or_default() += 1;
+                    }
+
+                    if let Some((player, from)) = carriers.remove(&flag) {
+                        runs.push(FlagRun {
+                            player,
+                            flag,
+                            from,
+                            to: e.time,
+                            is_captured,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // flags still carried at end of match
+        for (flag, (player, from)) in carriers {
+            runs.push(FlagRun {
+                player,
+                flag,
+                from,
+                to: end,
+                is_captured: false,
+            });
+        }
+        runs.sort_by_key(|r| r.from);
+
+        Self {
+            events,
+            captures,
+            runs,
+            rune_pickups: vec![],
+            rune_runs: vec![],
+        }
+    }
+
+    pub fn carry_time_per_player(&self) -> HashMap<String, Duration> {
+        let mut carry_time: HashMap<String, Duration> = HashMap::new();
+
+        for run in self.runs.iter() {
+            *carry_time.entry(run.player.clone()).or_default() += run.duration();
+        }
+
+        carry_time
+    }
+
+    pub fn rune_time_per_player(&self) -> HashMap<String, HashMap<Rune, Duration>> {
+        let mut rune_time: HashMap<String, HashMap<Rune, Duration>> = HashMap::new();
+
+        for run in self.rune_runs.iter() {
+            *rune_time
+                .entry(run.player.clone())
+                .or_default()
+                .entry(run.rune)
+                .or_default() += run.duration();
+        }
+
+        rune_time
+    }
+
+    // frags made while holding a rune
+    pub fn rune_frags_per_player(&self) -> HashMap<String, u32> {
+        let mut rune_frags: HashMap<String, u32> = HashMap::new();
+
+        for run in self.rune_runs.iter() {
+            *rune_frags.entry(run.player.clone()).or_default() += run.frags;
+        }
+
+        rune_frags
+    }
+
+    // time from pickup to capture
+    pub fn capture_durations(&self) -> Vec<Duration> {
+        self.runs
+            .iter()
+            .filter(|r| r.is_captured)
+            .map(|r| r.duration())
+            .collect()
+    }
+}
+
+pub fn ctf_events(data: &[u8]) -> Vec<CtfEvent> {
+    flag_prints(data)
+        .into_iter()
+        .filter_map(|(index, time, print)| {
+            let print_u = quake_text::bytestr::to_unicode(&print);
+            let (event, flag) = FlagEvent::with_flag(&print_u).ok()?;
+            Some(CtfEvent {
+                index,
+                time,
+                event,
+                flag,
+            })
+        })
+        .collect()
+}
+
+pub fn ctf_stats(data: &[u8]) -> CtfStats {
+    let end = demo_duration(data).unwrap_or_default();
+    let (rune_pickups, rune_runs) = rune_timeline(data);
+    CtfStats {
+        rune_pickups,
+        rune_runs,
+        ..CtfStats::from_events(ctf_events(data), end)
+    }
+}
+
+pub fn rune_pickups(data: &[u8]) -> Vec<RunePickup> {
+    rune_timeline(data).0
+}
+
+pub fn rune_runs(data: &[u8]) -> Vec<RuneRun> {
+    rune_timeline(data).1
+}
+
+// pickups from the KTX print sent to the player (client slot from the frame target),
+// a rune is held until the player dies, picks up another rune or leaves
+fn rune_timeline(data: &[u8]) -> (Vec<RunePickup>, Vec<RuneRun>) {
+    let timeline = name_timeline(data);
     let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut pickups: Vec<RunePickup> = vec![];
+    let mut runs: Vec<RuneRun> = vec![];
+    let mut current: HashMap<u8, RuneRun> = HashMap::new();
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += info.duration;
+
+        if info.body_size == 0 || info.is_hidden {
+            index += info.size;
+            continue;
+        }
+
+        let time = Duration::from_millis(total_ms as u64);
+        let names = timeline.names_at(info.index);
+        let number_of = |name: &str| names.iter().find(|(_, n)| *n == name).map(|(k, _)| *k);
+
+        let left: Vec<u8> = current
+            .keys()
+            .filter(|n| !names.contains_key(n))
+            .copied()
+            .collect();
+        for number in left {
+            if let Some(run) = current.remove(&number) {
+                runs.push(RuneRun { to: time, ..run });
+            }
+        }
+
+        for message in messages(&data[info.body_range.clone()]) {
+            let Message::Print(p) = message else {
+                continue;
+            };
+
+            match p.id {
+                PrintId::High if info.target == Target::Single => {
+                    let Some(rune) = Rune::from_pickup_print(&p.content) else {
+                        continue;
+                    };
+                    let Some(number) = info.player_number(data) else {
+                        continue;
+                    };
+                    let Some(player) = names.get(&number) else {
+                        continue;
+                    };
+
+                    pickups.push(RunePickup {
+                        index: info.index,
+                        time,
+                        player: player.clone(),
+                        rune,
+                    });
+
+                    let run = RuneRun {
+                        player: player.clone(),
+                        rune,
+                        from: time,
+                        to: time,
+                        frags: 0,
+                    };
+                    if let Some(previous) = current.insert(number, run) {
+                        runs.push(RuneRun {
+                            to: time,
+                            ..previous
+                        });
+                    }
+                }
+                PrintId::Medium => {
+                    let obituary = quake_text::bytestr::to_unicode(&p.content);
+                    let name_values: Vec<String> = names.values().cloned().collect();
+                    let Ok(event) =
+                        FragEvent::from_print_and_names(obituary.trim_end(), &name_values)
+                    else {
+                        continue;
+                    };
+
+                    let (killer, victim) = match event {
+                        FragEvent::Frag { killer, victim } => (Some(killer), Some(victim)),
+                        FragEvent::Death { player }
+                        | FragEvent::Suicide { player }
+                        | FragEvent::SuicideByWeapon { player } => (None, Some(player)),
+                        FragEvent::TeamkillByUnknown { victim } => (None, Some(victim)),
+                        FragEvent::Teamkill { .. } => (None, None),
+                    };
+
+                    let killer_number = killer.and_then(|k| number_of(&k));
+                    if let Some(run) = killer_number.and_then(|k| current.get_mut(&k)) {
+                        run.frags += 1;
+                    }
+
+                    let victim_number = victim.and_then(|v| number_of(&v));
+                    if let Some(run) = victim_number.and_then(|v| current.remove(&v)) {
+                        runs.push(RuneRun { to: time, ..run });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        index += info.size;
+    }
 
-    let mut prints: Vec<Vec<u8>> = vec![];
+    // runes still held at end of demo
+    let end = Duration::from_millis(total_ms as u64);
+    runs.extend(current.into_values().map(|run| RuneRun { to: end, ..run }));
+    runs.sort_by_key(|r| (r.from, r.player.clone()));
+    (pickups, runs)
+}
+
+pub fn flag_events_per_player_name(data: &[u8]) -> HashMap<String, PlayerFlagEvents> {
+    let mut player_flag_events: HashMap<String, PlayerFlagEvents> = HashMap::new();
+
+    for e in ctf_events(data) {
+        let Some(player) = e.event.player() else {
+            continue;
+        };
+        let pfe = player_flag_events.entry(player.to_string()).or_default();
+
+        match e.event {
+            FlagEvent::CapturedFlag { .. } => pfe.captures += 1,
+            FlagEvent::DefendsFlag { .. } => pfe.defends += 1,
+            FlagEvent::DefendsFlagCarrier { .. } => pfe.carrier_defends += 1,
+            FlagEvent::DefendsFlagCarrierVsAggressive { .. } => {
+                pfe.carrier_defends_vs_aggressive += 1
+            }
+            FlagEvent::GotFlag { .. } => pfe.pickups += 1,
+            FlagEvent::LostFlag { .. } => pfe.drops += 1,
+            FlagEvent::ReturnedFlag { .. } => pfe.returns += 1,
+            FlagEvent::ReturnedFlagAssist { .. } => pfe.carrier_frags += 1,
+            FlagEvent::FlagReturned => {}
+        }
+    }
+
+    player_flag_events
+}
+
+// flag prints (name and message may be split into separate prints) with frame index and game time
+fn flag_prints(data: &[u8]) -> Vec<(usize, Duration, Vec<u8>)> {
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut prints: Vec<(usize, Duration, Vec<u8>)> = vec![];
 
     while let Ok(frame_info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += frame_info.duration;
+
         if frame_info.body_size == 0 || data[frame_info.clone().body_range].find(b"flag").is_none()
         {
             index += frame_info.size;
             continue;
         }
 
-        let mut body = Cursor::new(&data[frame_info.clone().body_range]);
+        let time = Duration::from_millis(total_ms as u64);
         let mut current_print: Vec<u8> = vec![];
 
-        while let Ok(mt) = body.read_message_type() {
-            if mt == MessageType::Print {
-                if let Ok(p) = body.read_print() {
-                    if p.id == PrintId::High && !p.content.is_empty() {
-                        if current_print.is_empty() {
-                            current_print.extend_from_slice(&p.content);
-                        } else {
-                            if is_message_suffix(&p.content) {
-                                current_print.extend_from_slice(&p.content);
-                            }
-
-                            prints.push(current_print.clone());
-                            current_print = vec![];
-                        }
+        for message in messages(&data[frame_info.clone().body_range]) {
+            let Message::Print(p) = message else {
+                continue;
+            };
+
+            if p.id == PrintId::High && !p.content.is_empty() {
+                if current_print.is_empty() {
+                    current_print.extend_from_slice(&p.content);
+                } else {
+                    if is_message_suffix(&p.content) {
+                        current_print.extend_from_slice(&p.content);
                     }
+
+                    prints.push((frame_info.index, time, current_print.clone()));
+                    current_print = vec![];
                 }
             }
         }
 
         if !current_print.is_empty() {
-            prints.push(current_print);
+            prints.push((frame_info.index, time, current_print));
         }
 
         index += frame_info.size;
     }
 
-    let mut player_flag_events: HashMap<String, PlayerFlagEvents> = HashMap::new();
-
-    for print in prints {
-        let print_u = quake_text::bytestr::to_unicode(&print);
-
-        match FlagEvent::try_from(print_u.as_str()) {
-            Ok(event) => match event {
-                FlagEvent::CapturedFlag { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.captures += 1;
-                }
-                FlagEvent::DefendsFlag { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.defends += 1;
-                }
-                FlagEvent::DefendsFlagCarrier { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.carrier_defends += 1;
-                }
-                FlagEvent::DefendsFlagCarrierVsAggressive { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.carrier_defends_vs_aggressive += 1;
-                }
-                FlagEvent::GotFlag { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.pickups += 1;
-                }
-                FlagEvent::ReturnedFlag { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.returns += 1;
-                }
-                FlagEvent::ReturnedFlagAssist { player } => {
-                    let pfe = player_flag_events.entry(player).or_default();
-                    pfe.carrier_frags += 1;
-                }
-            },
-            Err(_e) => {
-                // println!("UNKNOWN {:?}", e);
-            }
-        }
-    }
-
-    player_flag_events
+    prints
 }
 
 fn is_message_suffix(print: &[u8]) -> bool {
@@ -112,6 +444,7 @@ fn is_message_suffix(print: &[u8]) -> bool {
         flagprint::X_RETURNED_FLAG.to_vec(),
         flagprint::X_GOT_FLAG.to_vec(),
         flagprint::X_CAPTURED_FLAG.to_vec(),
+        flagprint::X_LOST_FLAG.to_vec(),
     ]
     .iter()
     .flatten()
@@ -126,6 +459,8 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::qw::flagprint;
+    use crate::testdemo::{print, to_all, to_single};
 
     #[test]
     fn test_flag_events_per_player_name() -> Result<()> {
@@ -232,4 +567,291 @@ mod tests {
 
         Ok(())
     }
+
+    fn ctf_event(secs: u64, event: FlagEvent, flag: Option<FlagColor>) -> CtfEvent {
+        CtfEvent {
+            index: 0,
+            time: Duration::from_secs(secs),
+            event,
+            flag,
+        }
+    }
+
+    #[test]
+    fn test_ctf_stats_from_events() {
+        let foo = || "FOO".to_string();
+        let bar = || "BAR".to_string();
+        let events = vec![
+            ctf_event(
+                10,
+                FlagEvent::GotFlag { player: foo() },
+                Some(FlagColor::Red),
+            ),
+            ctf_event(
+                12,
+                FlagEvent::GotFlag { player: bar() },
+                Some(FlagColor::Blue),
+            ),
+            ctf_event(
+                15,
+                FlagEvent::LostFlag { player: bar() },
+                Some(FlagColor::Blue),
+            ),
+            ctf_event(
+                16,
+                FlagEvent::ReturnedFlag { player: foo() },
+                Some(FlagColor::Blue),
+            ),
+            ctf_event(
+                30,
+                FlagEvent::CapturedFlag { player: foo() },
+                Some(FlagColor::Red),
+            ),
+            ctf_event(
+                40,
+                FlagEvent::GotFlag { player: bar() },
+                Some(FlagColor::Blue),
+            ),
+        ];
+        let stats = CtfStats::from_events(events, Duration::from_secs(45));
+
+        assert_eq!(stats.captures, HashMap::from([(FlagColor::Blue, 1)]));
+        assert_eq!(
+            stats.runs,
+            vec![
+                FlagRun {
+                    player: foo(),
+                    flag: FlagColor::Red,
+                    from: Duration::from_secs(10),
+                    to: Duration::from_secs(30),
+                    is_captured: true,
+                },
+                FlagRun {
+                    player: bar(),
+                    flag: FlagColor::Blue,
+                    from: Duration::from_secs(12),
+                    to: Duration::from_secs(15),
+                    is_captured: false,
+                },
+                FlagRun {
+                    player: bar(),
+                    flag: FlagColor::Blue,
+                    from: Duration::from_secs(40),
+                    to: Duration::from_secs(45),
+                    is_captured: false,
+                },
+            ]
+        );
+        assert_eq!(
+            stats.carry_time_per_player(),
+            HashMap::from([
+                (foo(), Duration::from_secs(20)),
+                (bar(), Duration::from_secs(8)),
+            ])
+        );
+        assert_eq!(stats.capture_durations(), vec![Duration::from_secs(20)]);
+    }
+
+    #[test]
+    fn test_ctf_stats() -> Result<()> {
+        let stats = ctf_stats(&read("tests/files/ctf_blue_vs_red[ctf5]20240520-1925.mvd")?);
+
+        // caps and pickups per ktxstats
+        assert_eq!(stats.captures, HashMap::from([(FlagColor::Red, 9)]));
+        assert_eq!(stats.runs.len(), 30);
+        assert_eq!(stats.capture_durations().len(), 9);
+        assert!(stats
+            .capture_durations()
+            .iter()
+            .all(|d| *d > Duration::ZERO));
+
+        let carry_time = stats.carry_time_per_player();
+        assert_eq!(carry_time.len(), 7);
+        assert!(carry_time.contains_key("CCTäêåöõìóë"));
+        assert!(!carry_time.contains_key("ì÷ú\u{AD}xunito"));
+
+        Ok(())
+    }
+
+    // flag carried until the end of match print
+    #[test]
+    fn test_ctf_stats_match_end() {
+        let latin1 = |value: &str| -> Vec<u8> { value.chars().map(|c| c as u8).collect() };
+        let flag_print = |name: &[u8], message: &str| -> Vec<u8> {
+            [print(2, name), print(2, &latin1(message))].concat()
+        };
+        let data = [
+            to_all(0, &flag_print(b"FOO", flagprint::X_GOT_FLAG[1])),
+            to_all(200, &flag_print(b"FOO", flagprint::X_CAPTURED_FLAG[1])),
+            to_all(100, &flag_print(b"BAR", flagprint::X_GOT_FLAG[0])),
+            to_all(100, &print(2, b"The match is over\n")),
+            to_all(250, &[]),
+        ]
+        .concat();
+
+        let stats = ctf_stats(&data);
+        let foo = || "FOO".to_string();
+        let bar = || "BAR".to_string();
+
+        assert_eq!(
+            stats.events,
+            vec![
+                CtfEvent {
+                    index: 0,
+                    time: Duration::ZERO,
+                    event: FlagEvent::GotFlag { player: foo() },
+                    flag: Some(FlagColor::Blue),
+                },
+                CtfEvent {
+                    index: 35,
+                    time: Duration::from_millis(200),
+                    event: FlagEvent::CapturedFlag { player: foo() },
+                    flag: Some(FlagColor::Blue),
+                },
+                CtfEvent {
+                    index: 75,
+                    time: Duration::from_millis(300),
+                    event: FlagEvent::GotFlag { player: bar() },
+                    flag: Some(FlagColor::Red),
+                },
+            ]
+        );
+        assert_eq!(stats.captures, HashMap::from([(FlagColor::Red, 1)]));
+
+        // carried until end of match, not end of demo
+        let runs: Vec<(String, FlagColor, u128, u128, bool)> = stats
+            .runs
+            .iter()
+            .map(|r| {
+                let (from, to) = (r.from.as_millis(), r.to.as_millis());
+                (r.player.clone(), r.flag, from, to, r.is_captured)
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                (foo(), FlagColor::Blue, 0, 200, true),
+                (bar(), FlagColor::Red, 300, 400, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rune_from_pickup_print() {
+        assert_eq!(
+            Rune::from_pickup_print(b"You got the \xe8\xe1\xf3\xf4\xe5 rune\n"),
+            Some(Rune::Haste)
+        );
+        assert_eq!(
+            Rune::from_pickup_print(b"You got the Resistance rune\n"),
+            Some(Rune::Resistance)
+        );
+        assert_eq!(Rune::from_pickup_print(b"You got the flag\n"), None);
+    }
+
+    #[test]
+    fn test_rune_runs() {
+        let data = [
+            to_all(
+                0,
+                b"\x28\x01\x00\x00\x00\x00\\name\\FOO\0\x28\x02\x00\x00\x00\x00\\name\\BAR\0",
+            ),
+            to_single(
+                100,
+                1,
+                &print(2, b"You got the \xe8\xe1\xf3\xf4\xe5 rune\n"),
+            ),
+            to_all(200, &print(1, b"BAR rides FOO's rocket\n")),
+            to_all(50, &print(1, b"FOO died\n")),
+            // chat and prints to all are not pickups
+            to_all(0, &print(3, b"You got the strength rune\n")),
+            to_all(0, &print(2, b"You got the strength rune\n")),
+            to_single(50, 2, &print(2, b"You got the strength rune\n")),
+            to_all(250, &[]),
+        ]
+        .concat();
+
+        assert_eq!(
+            rune_pickups(&data),
+            vec![
+                RunePickup {
+                    index: 38,
+                    time: Duration::from_millis(100),
+                    player: "FOO".to_string(),
+                    rune: Rune::Haste,
+                },
+                RunePickup {
+                    index: 190,
+                    time: Duration::from_millis(400),
+                    player: "BAR".to_string(),
+                    rune: Rune::Strength,
+                },
+            ]
+        );
+        assert_eq!(
+            rune_runs(&data),
+            vec![
+                RuneRun {
+                    player: "FOO".to_string(),
+                    rune: Rune::Haste,
+                    from: Duration::from_millis(100),
+                    to: Duration::from_millis(350),
+                    frags: 1,
+                },
+                RuneRun {
+                    player: "BAR".to_string(),
+                    rune: Rune::Strength,
+                    from: Duration::from_millis(400),
+                    to: Duration::from_millis(650),
+                    frags: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            CtfStats {
+                rune_runs: rune_runs(&data),
+                ..Default::default()
+            }
+            .rune_time_per_player(),
+            HashMap::from([
+                (
+                    "FOO".to_string(),
+                    HashMap::from([(Rune::Haste, Duration::from_millis(250))])
+                ),
+                (
+                    "BAR".to_string(),
+                    HashMap::from([(Rune::Strength, Duration::from_millis(250))])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rune_runs_ctf5() -> Result<()> {
+        let data = read("tests/files/ctf_blue_vs_red[ctf5]20240520-1925.mvd")?;
+        let stats = ctf_stats(&data);
+        let rune_time = stats.rune_time_per_player();
+
+        // rune seconds per ktxstats, ktx counts whole seconds
+        for (player, rune, seconds) in [
+            ("ì÷ú\u{AD}velocity", Rune::Resistance, 100),
+            ("ì÷ú\u{AD}velocity", Rune::Regeneration, 61),
+            ("ì÷ú\u{AD}lethalwiz", Rune::Strength, 67),
+            ("lwz-brunelson", Rune::Resistance, 173),
+            ("CCTãáöåòïî", Rune::Regeneration, 192),
+        ] {
+            let held = rune_time
+                .get(player)
+                .and_then(|r| r.get(&rune))
+                .map(|d| d.as_secs_f64())
+                .unwrap_or_default();
+            assert!(
+                (held - seconds as f64).abs() < 2.0,
+                "{player} {rune:?} {held}"
+            );
+        }
+        assert_eq!(stats.rune_pickups.len(), stats.rune_runs.len());
+
+        Ok(())
+    }
 }
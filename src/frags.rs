@@ -1,138 +1,132 @@
 use std::collections::HashMap;
-use std::io::Cursor;
 
-use anyhow::{anyhow as e, Result};
+use bstr::ByteSlice;
 
-use crate::clients;
+use crate::matchdate;
 use crate::qw::fragevent::FragEvent;
 use crate::qw::frame;
-use crate::qw::message::message_type::ReadMessageType;
-use crate::qw::message::print::ReadPrint;
-use crate::qw::message::update_frags::ReadUpdateFrags;
-use crate::qw::message::Print;
-use crate::qw::prot::{MessageType, PrintId};
+use crate::qw::message::{messages, Message};
+use crate::qw::prot::PrintId;
+use crate::scoreboard::scoreboard_timeline;
+use crate::userinfo::name_timeline;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerFragEvents {
+    pub frags: u32,
+    pub deaths: u32,
+    pub suicides: u32,
+    pub teamkills: u32,
+}
+
+pub fn frags_per_player_number(data: &[u8]) -> HashMap<u8, i32> {
+    scoreboard_timeline(data).final_frags()
+}
 
 pub fn frags_per_player_name(data: &[u8]) -> HashMap<String, i32> {
+    let names = name_timeline(data).last_names();
+
+    frags_per_player_number(data)
+        .iter()
+        .filter_map(|(number, frags)| Some((names.get(number)?.clone(), *frags)))
+        .collect()
+}
+
+pub fn frag_events_per_player_number(data: &[u8]) -> HashMap<u8, PlayerFragEvents> {
+    let timeline = name_timeline(data);
+    let match_start = data.find(matchdate::MATCHDATE_NEEDLE).unwrap_or(0);
     let mut index = 0;
-    let mut print_frames: Vec<(Print, frame::Info)> = vec![];
+    let mut frags: HashMap<u8, i32> = HashMap::new();
+    let mut events: HashMap<u8, PlayerFragEvents> = HashMap::new();
 
-    while let Ok(frame_info) = frame::Info::from_data_and_index(data, index) {
-        if frame_info.body_size == 0 {
-            index += frame_info.size;
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        if info.body_size == 0 || info.is_hidden {
+            index += info.size;
             continue;
         }
 
-        let mut body = Cursor::new(&data[frame_info.clone().body_range]);
+        let mut obituaries: Vec<String> = vec![];
+        let mut frag_losers: Vec<u8> = vec![];
 
-        if body
-            .read_message_type()
-            .is_ok_and(|t| t == MessageType::Print)
-        {
-            if let Ok(p) = body.read_print() {
-                if !p.content.is_empty() && PrintId::Medium == p.id {
-                    print_frames.push((p, frame_info.clone()))
+        for message in messages(&data[info.body_range.clone()]) {
+            match message {
+                Message::Print(p) if p.id == PrintId::Medium && !p.content.is_empty() => {
+                    let content_u = quake_text::bytestr::to_unicode(&p.content);
+                    obituaries.push(content_u.trim_end().to_string());
+                }
+                Message::UpdateFrags(u) => {
+                    let previous = frags.insert(u.player_number, u.frags as i32);
+                    if previous.is_some_and(|f| (u.frags as i32) < f) {
+                        frag_losers.push(u.player_number);
+                    }
                 }
+                _ => {}
             }
         }
 
-        index += frame_info.size;
-    }
+        if obituaries.is_empty() || info.index < match_start {
+            index += info.size;
+            continue;
+        }
 
-    let mut frags: HashMap<String, i32> = HashMap::new();
+        let names = timeline.names_at(info.index);
+        let name_values: Vec<String> = names.values().cloned().collect();
+        let number_of = |name: &str| {
+            names
+                .iter()
+                .find(|(_, n)| n.as_str() == name)
+                .map(|(number, _)| *number)
+        };
 
-    for (print, frame_info) in print_frames {
-        let content_u = quake_text::bytestr::to_unicode(&print.content);
+        for obituary in obituaries {
+            let Ok(event) = FragEvent::from_print_and_names(&obituary, &name_values) else {
+                continue;
+            };
 
-        match FragEvent::try_from(content_u.trim_end()) {
-            Ok(event) => match event {
-                FragEvent::Frag { killer, .. } => {
-                    let killer = frags.entry(killer).or_insert(0);
-                    *killer += 1;
+            match event {
+                FragEvent::Frag { killer, victim } => {
+                    if let Some(number) = number_of(&killer) {
+                        events.entry(number).or_default().frags += 1;
+                    }
+                    if let Some(number) = number_of(&victim) {
+                        events.entry(number).or_default().deaths += 1;
+                    }
                 }
                 FragEvent::Death { player } => {
-                    let player = frags.entry(player).or_insert(0);
-                    *player -= 1;
-                }
-                FragEvent::Suicide { player } => {
-                    let player = frags.entry(player).or_insert(0);
-                    *player -= 2;
+                    if let Some(number) = number_of(&player) {
+                        events.entry(number).or_default().deaths += 1;
+                    }
                 }
-                FragEvent::SuicideByWeapon { player } => {
-                    let player = frags.entry(player).or_insert(0);
-                    *player -= 1;
+                FragEvent::Suicide { player } | FragEvent::SuicideByWeapon { player } => {
+                    if let Some(number) = number_of(&player) {
+                        let pfe = events.entry(number).or_default();
+                        pfe.deaths += 1;
+                        pfe.suicides += 1;
+                    }
                 }
                 FragEvent::Teamkill { killer } => {
-                    let killer = frags.entry(killer).or_insert(0);
-                    *killer -= 1;
-                }
-                FragEvent::TeamkillByUnknown { victim } => {
-                    if let Ok(name) = find_team_killer(data, frame_info.index, &victim) {
-                        let killer = frags.entry(name).or_insert(0);
-                        *killer -= 1;
+                    if let Some(number) = number_of(&killer) {
+                        events.entry(number).or_default().teamkills += 1;
                     }
                 }
-            },
-            Err(_e) => {
-                // println!("UNKNOWN {:?}", e);
-            }
-        }
-    }
-
-    frags
-}
-
-fn find_team_killer(data: &[u8], index: usize, victim_name: &str) -> Result<String> {
-    let mut index = index;
-    let mut frame_count: usize = 1;
-    let mut frag_update_player_numbers: Vec<u8> = vec![];
-    const MAX_FRAME_COUNT: usize = 4;
-
-    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
-        if frame_count >= MAX_FRAME_COUNT {
-            break;
-        } else if info.body_size == 0 {
-            index += info.size;
-            continue;
-        }
-
-        let mut body = Cursor::new(&data[info.body_range.clone()]);
+                FragEvent::TeamkillByUnknown { victim } => {
+                    let victim_number = number_of(&victim);
 
-        if frame_count == 1 {
-            body.read_print().ok();
-        }
+                    if let Some(number) = victim_number {
+                        events.entry(number).or_default().deaths += 1;
+                    }
 
-        if body
-            .read_message_type()
-            .is_ok_and(|t| t == MessageType::UpdateFrags)
-        {
-            if let Ok(u) = body.read_update_frags() {
-                frag_update_player_numbers.push(u.player_number);
+                    if let Some(killer) = frag_losers.iter().find(|n| Some(**n) != victim_number) {
+                        events.entry(*killer).or_default().teamkills += 1;
+                    }
+                }
             }
         }
 
         index += info.size;
-        frame_count += 1;
     }
 
-    if frag_update_player_numbers.is_empty() {
-        return Err(e!("Unable to find nearby frag updates"));
-    }
-
-    let clients = clients::clients(data)?;
-    let Some(victim_client) = clients.iter().find(|c| c.name == *victim_name) else {
-        return Err(e!("Unable to find victim"));
-    };
-
-    let killer = frag_update_player_numbers
-        .iter()
-        .filter_map(|player_number| clients.iter().find(|c| c.number == *player_number))
-        .find(|c| c.team == victim_client.team && c.name != victim_client.name)
-        .map(|c| c.name.clone());
-
-    match killer {
-        Some(killer) => Ok(killer),
-        None => Err(e!("Unable to find team killer")),
-    }
+    events
 }
 
 #[cfg(test)]
@@ -189,4 +183,77 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_frags_per_player_number() -> Result<()> {
+        {
+            let demo_data = read("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")?;
+            let frags_map = frags_per_player_number(&demo_data);
+            assert_eq!(frags_map.len(), 3);
+            assert_eq!(frags_map.get(&0), Some(&19));
+            assert_eq!(frags_map.get(&1), Some(&0));
+            assert_eq!(frags_map.get(&2), Some(&20));
+        }
+
+        {
+            let demo_data = read("tests/files/2on2_sf_vs_red[frobodm2]220104-0915.mvd")?;
+            let frags_map = frags_per_player_number(&demo_data);
+            assert_eq!(frags_map.get(&0), Some(&1));
+            assert_eq!(frags_map.get(&1), Some(&13));
+            assert_eq!(frags_map.get(&3), Some(&-6));
+            assert_eq!(frags_map.get(&4), Some(&7));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_frag_events_per_player_number() -> Result<()> {
+        {
+            let demo_data = read("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")?;
+            let events = frag_events_per_player_number(&demo_data);
+            assert_eq!(events.len(), 2);
+            assert_eq!(
+                events.get(&0),
+                Some(&PlayerFragEvents {
+                    frags: 21,
+                    deaths: 22,
+                    suicides: 2,
+                    teamkills: 0,
+                })
+            );
+            assert_eq!(
+                events.get(&2),
+                Some(&PlayerFragEvents {
+                    frags: 20,
+                    deaths: 21,
+                    suicides: 0,
+                    teamkills: 0,
+                })
+            );
+        }
+
+        {
+            let demo_data = read("tests/files/ffa_5[dm4]20240501-1229.mvd")?;
+            let events = frag_events_per_player_number(&demo_data);
+            assert_eq!(events.len(), 5);
+            assert_eq!(
+                events.get(&5),
+                Some(&PlayerFragEvents {
+                    frags: 8,
+                    deaths: 11,
+                    suicides: 0,
+                    teamkills: 0,
+                })
+            );
+        }
+
+        {
+            let demo_data = read("tests/files/4on4_-s-_vs_pol[dm2]20241118-2135.mvd")?;
+            let events = frag_events_per_player_number(&demo_data);
+            assert_eq!(events.values().map(|e| e.teamkills).sum::<u32>(), 9);
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,140 @@
+use core::time::Duration;
+
+pub use crate::qw::message::playerinfo::Playerinfo;
+pub use crate::qw::message::serverinfo::ServerinfoRef;
+pub use crate::qw::message::setinfo::SetinfoRef;
+pub use crate::qw::message::update_frags::UpdateFrags;
+pub use crate::qw::message::update_ping::UpdatePing;
+pub use crate::qw::message::update_pl::UpdatePl;
+pub use crate::qw::message::update_stat::UpdateStat;
+pub use crate::qw::message::update_userinfo::UpdateUserinfoRef;
+pub use crate::qw::message::{message_refs, MessageRef, PrintRef};
+pub use crate::qw::prot::{MessageType, PrintId};
+
+use crate::qw::frame;
+
+// borrowed from demo data, nothing is copied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Frame<'a> {
+    pub index: usize,
+    pub time: Duration, // game time
+    pub is_hidden: bool,
+    pub data: &'a [u8], // header and body
+    pub body: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    pub fn messages(&self) -> impl Iterator<Item = MessageRef<'a>> {
+        let body = match self.is_hidden {
+            true => &[],
+            false => self.body,
+        };
+        message_refs(body)
+    }
+
+    pub fn prints(&self) -> impl Iterator<Item = PrintRef<'a>> {
+        self.messages().filter_map(|message| match message {
+            MessageRef::Print(print) if !print.content.is_empty() => Some(print),
+            _ => None,
+        })
+    }
+}
+
+pub struct Frames<'a> {
+    data: &'a [u8],
+    index: usize,
+    total_ms: u64,
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Frame<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let info = frame::Info::from_data_and_index(self.data, self.index).ok()?;
+        self.index += info.size;
+        self.total_ms += info.duration as u64;
+
+        Some(Frame {
+            index: info.index,
+            time: Duration::from_millis(self.total_ms),
+            is_hidden: info.is_hidden,
+            data: &self.data[info.index..info.index + info.size],
+            body: &self.data[info.body_range],
+        })
+    }
+}
+
+// ends at the first frame that can not be read
+pub fn frames(data: &[u8]) -> Frames<'_> {
+    Frames {
+        data,
+        index: 0,
+        total_ms: 0,
+    }
+}
+
+// prints of all frames, duplicates at the same time (same print to several clients) removed
+pub fn print_refs(data: &[u8]) -> impl Iterator<Item = PrintRef<'_>> {
+    let mut previous: Option<(Duration, PrintRef)> = None;
+
+    frames(data)
+        .flat_map(|frame| frame.prints().map(move |print| (frame.time, print)))
+        .filter(move |current| {
+            let is_duplicate = previous == Some(*current);
+            previous = Some(*current);
+            !is_duplicate
+        })
+        .map(|(_, print)| print)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use quake_text::bytestr::to_ascii;
+
+    use crate::prints::timed_prints;
+    use crate::qw::prot::PrintId;
+
+    use super::*;
+
+    const DEMO_PATH: &str = "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd";
+
+    #[test]
+    fn test_frames() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+
+        let all: Vec<Frame> = frames(&data).collect();
+        assert_eq!(all.len(), 29380);
+        assert_eq!(all.iter().map(|f| f.data.len()).sum::<usize>(), data.len());
+        assert_eq!(all[0].index, 0);
+        assert_eq!(
+            all.last().map(|f| f.time),
+            Some(Duration::from_millis(196248))
+        );
+        assert!(all.iter().all(|f| f.data.ends_with(f.body)));
+        assert_eq!(frames(&[]).count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_refs() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        let prints: Vec<PrintRef> = print_refs(&data).collect();
+
+        assert_eq!(prints[0].id, PrintId::High);
+        assert_eq!(to_ascii(prints[0].content), "KabOOm is ready_");
+        assert_eq!(
+            prints.iter().map(|p| p.to_print()).collect::<Vec<_>>(),
+            timed_prints(&data)
+                .into_iter()
+                .map(|p| p.print)
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+}
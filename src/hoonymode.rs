@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bstr::ByteSlice;
+use quake_text::bytestr::to_ascii;
+
+use crate::qw::frame;
+use crate::qw::message::{messages, Message};
+use crate::serverinfo;
+use crate::userinfo::name_timeline;
+
+const SET_POINT_NEEDLE: &str = "* Set Point *";
+const POINT_START_STUFFTEXT: &str = "//ktx matchstart"; // sent by KTX at the start of every point
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HoonymodePoint {
+    pub number: u32,
+    pub start: Duration,
+    pub end: Duration,
+    pub winner: Option<String>,
+    pub score: HashMap<String, i32>, // running score after the point
+    pub spawns: HashMap<String, [i32; 3]>, // origin (rounded) at the start of the point
+    pub is_set_point: bool,
+}
+
+impl HoonymodePoint {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+pub fn is_hoonymode(data: &[u8]) -> bool {
+    serverinfo(data).is_ok_and(|s| s.mode == Some("hoonymode".to_string()))
+}
+
+// each frag ends a point, the next point starts when players have respawned
+pub fn hoonymode_points(data: &[u8]) -> Vec<HoonymodePoint> {
+    if !is_hoonymode(data) {
+        return vec![];
+    }
+
+    let names = name_timeline(data);
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut points: Vec<HoonymodePoint> = vec![];
+    let mut score: HashMap<u8, i32> = HashMap::new();
+    let mut origins: HashMap<u8, [f32; 3]> = HashMap::new();
+    let mut spawns: HashMap<u8, [i32; 3]> = HashMap::new();
+    let mut point_start: Option<Duration> = None;
+    let mut is_set_point = false;
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += info.duration;
+
+        if info.body_size == 0 || info.is_hidden {
+            index += info.size;
+            continue;
+        }
+
+        let time = Duration::from_millis(total_ms as u64);
+
+        for message in messages(&data[info.body_range.clone()]) {
+            match message {
+                Message::CenterPrint(content) if to_ascii(&content).contains(SET_POINT_NEEDLE) => {
+                    is_set_point = true;
+                }
+                Message::Stufftext(content)
+                    if content.trim_end() == POINT_START_STUFFTEXT.as_bytes() =>
+                {
+                    point_start = Some(time);
+                    spawns.clear();
+                }
+                Message::Playerinfo(p) => {
+                    let origin = origins.entry(p.player_number).or_default();
+
+                    for (value, update) in origin.iter_mut().zip(p.origin) {
+                        *value = update.unwrap_or(*value);
+                    }
+
+                    // first position of the player after the point started
+                    if point_start.is_some() {
+                        spawns
+                            .entry(p.player_number)
+                            .or_insert(origin.map(|v| v.round() as i32));
+                    }
+                }
+                Message::UpdateFrags(u) => {
+                    let previous = score.insert(u.player_number, u.frags as i32);
+
+                    let Some(start) = point_start else {
+                        continue;
+                    };
+
+                    if previous.map_or(u.frags > 0, |p| (u.frags as i32) > p) {
+                        let names_now = names.names_at(info.index);
+                        points.push(HoonymodePoint {
+                            number: points.len() as u32 + 1,
+                            start,
+                            end: time,
+                            winner: names_now.get(&u.player_number).cloned(),
+                            score: by_name(&score, &names_now),
+                            spawns: by_name(&spawns, &names_now),
+                            is_set_point,
+                        });
+                        point_start = None;
+                        is_set_point = false;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        index += info.size;
+    }
+
+    points
+}
+
+fn by_name<T: Copy>(values: &HashMap<u8, T>, names: &HashMap<u8, String>) -> HashMap<String, T> {
+    values
+        .iter()
+        .filter_map(|(n, v)| Some((names.get(n)?.clone(), *v)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_hoonymode_points() -> Result<()> {
+        assert_eq!(
+            hoonymode_points(&read(
+                "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd"
+            )?),
+            vec![]
+        );
+
+        let points = hoonymode_points(&read(
+            "tests/files/1on1_milton_vs_mushi[tron]20240616-1719.mvd",
+        )?);
+        assert_eq!(points.len(), 10);
+        assert_eq!(
+            points[0],
+            HoonymodePoint {
+                number: 1,
+                start: Duration::from_millis(10129),
+                end: Duration::from_millis(32583),
+                winner: Some("Milton".to_string()),
+                score: HashMap::from([("Milton".to_string(), 1), ("mushi".to_string(), 0)]),
+                spawns: HashMap::from([
+                    ("Milton".to_string(), [448, 256, 121]),
+                    ("mushi".to_string(), [-128, 464, 120]),
+                ]),
+                is_set_point: false,
+            }
+        );
+        assert_eq!(points[0].duration(), Duration::from_millis(22454));
+        assert_eq!(
+            points[1].spawns,
+            HashMap::from([
+                ("Milton".to_string(), [-128, 464, 121]),
+                ("mushi".to_string(), [448, 256, 121]),
+            ])
+        );
+        assert_eq!(
+            points[9],
+            HoonymodePoint {
+                number: 10,
+                start: Duration::from_millis(158726),
+                end: Duration::from_millis(168512),
+                winner: Some("Milton".to_string()),
+                score: HashMap::from([("Milton".to_string(), 8), ("mushi".to_string(), 2)]),
+                spawns: HashMap::from([
+                    ("Milton".to_string(), [-336, -368, 121]),
+                    ("mushi".to_string(), [448, 256, 121]),
+                ]),
+                is_set_point: true,
+            }
+        );
+        assert_eq!(
+            points
+                .iter()
+                .filter(|p| p.winner == Some("mushi".to_string()))
+                .count(),
+            2
+        );
+
+        Ok(())
+    }
+}
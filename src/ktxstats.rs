@@ -1,6 +1,6 @@
 use std::str::from_utf8;
 
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 use bstr::ByteSlice;
 pub use ktxstats::v3::KtxstatsV3;
 
@@ -9,26 +9,35 @@ use crate::qw::{block, frame};
 
 pub fn ktxstats_v3(data: &[u8]) -> Result<KtxstatsV3> {
     let stats_str = ktxstats_string(data)?;
-    ktxstats::v3::KtxstatsV3::try_from(stats_str.as_str()).map_err(|err| e!(err))
+    ktxstats::v3::KtxstatsV3::try_from(stats_str.as_str())
+        .map_err(|err| Error::InvalidKtxstats(err.to_string()))
 }
 
 pub fn ktxstats_string(data: &[u8]) -> Result<String> {
     const TOTAL_HEADER_SIZE: usize = frame::MULTI_HEADER_SIZE + block::HEADER_SIZE;
 
-    let Some(mut offset) = data.rfind(br#"{"version": "#) else {
-        return Err(e!("ktxstats not found"));
+    let Some(mut offset) = data
+        .rfind(br#"{"version": "#)
+        .and_then(|o| o.checked_sub(TOTAL_HEADER_SIZE))
+    else {
+        return Err(Error::NoKtxstats);
     };
-    offset -= TOTAL_HEADER_SIZE;
     let mut content = Vec::new();
 
     // read blocks
-    while let Ok(info) = block::Info::try_from(&data[offset + frame::MULTI_HEADER_SIZE..]) {
+    while let Some(Ok(info)) = data
+        .get(offset + frame::MULTI_HEADER_SIZE..)
+        .map(block::Info::try_from)
+    {
         if info.hidden_message != HiddenMessage::Demoinfo {
             break;
         }
 
         offset += TOTAL_HEADER_SIZE;
-        content.extend_from_slice(&data[offset..offset + info.body_size]);
+        let Some(body) = data.get(offset..offset + info.body_size) else {
+            return Err(Error::InvalidKtxstats("truncated block".to_string()));
+        };
+        content.extend_from_slice(body);
 
         if info.number == 0 {
             break;
@@ -37,7 +46,10 @@ pub fn ktxstats_string(data: &[u8]) -> Result<String> {
         offset += info.body_size;
     }
 
-    Ok(from_utf8(&content)?.to_string())
+    match from_utf8(&content) {
+        Ok(stats) => Ok(stats.to_string()),
+        Err(err) => Err(Error::InvalidKtxstats(err.to_string())),
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +61,23 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_ktxstats_string_invalid() {
+        assert!(matches!(
+            ktxstats_string(br#"{"version": 3}"#),
+            Err(Error::NoKtxstats)
+        ));
+
+        // block claims more content than available
+        let mut data = vec![0; frame::MULTI_HEADER_SIZE];
+        data.extend_from_slice(&[0xe8, 0x03, 0, 0, 3, 0, 0, 0]);
+        data.extend_from_slice(br#"{"version": 3}"#);
+        assert!(matches!(
+            ktxstats_string(&data),
+            Err(Error::InvalidKtxstats(_))
+        ));
+    }
+
     #[test]
     fn test_ktxstats_v3() -> Result<()> {
         let demo_data = read("tests/files/4on4_oeks_vs_tsq[dm2]20240426-1716.mvd")?;
@@ -85,10 +114,10 @@ mod tests {
         }
         {
             let demo_data = read("tests/files/wipeout_red_vs_blue[q3dm6qw]20240406-2028.mvd")?;
-            assert_eq!(
-                ktxstats_string(&demo_data).unwrap_err().to_string(),
-                "ktxstats not found"
-            );
+            assert!(matches!(
+                ktxstats_string(&demo_data),
+                Err(Error::NoKtxstats)
+            ));
         }
 
         Ok(())
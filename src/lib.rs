@@ -1,69 +1,238 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub use client::Client;
+#[cfg(feature = "std")]
 pub use player::Player;
+#[cfg(feature = "std")]
 pub use server::Server;
+#[cfg(feature = "std")]
 pub use team::Team;
 
-pub use crate::aborted::is_aborted;
+#[cfg(feature = "std")]
+pub use crate::aborted::{abort_vote, is_aborted};
+#[cfg(feature = "std")]
+pub use crate::batch::{batch, batch_for_each, BatchItem, Extractor, ReadMode};
+#[cfg(feature = "std")]
 pub use crate::clientinfo::{clientinfo, Clientinfo};
+#[cfg(feature = "std")]
 pub use crate::clients::{
     player_clients, player_names, spectator_clients, spectator_names, team_names,
 };
-pub use crate::duration::{countdown_duration, demo_duration, match_duration};
+#[cfg(feature = "std")]
+pub use crate::duration::{
+    countdown_duration, demo_duration, match_duration, match_duration_info, MatchDuration,
+};
+pub use crate::error::{Error, Result};
+#[cfg(feature = "std")]
 pub use crate::filename::filename;
-pub use crate::frags::frags_per_player_name;
+#[cfg(feature = "std")]
+pub use crate::fingerprint::{fingerprint, same_match};
+#[cfg(feature = "std")]
+pub use crate::flags::{ctf_events, ctf_stats};
+#[cfg(feature = "std")]
+pub use crate::frags::{frags_per_player_name, frags_per_player_number};
+pub use crate::frames::{frames, print_refs, Frame, MessageRef, PrintRef};
+#[cfg(feature = "std")]
+pub use crate::hoonymode::hoonymode_points;
+#[cfg(feature = "std")]
 pub use crate::ktxstats::{ktxstats_string, ktxstats_v3, KtxstatsV3};
-pub use crate::paused::is_paused;
+#[cfg(feature = "mmap")]
+pub use crate::mmap::mmap_file;
+#[cfg(feature = "std")]
+pub use crate::network::{network_samples, network_stats};
+#[cfg(feature = "std")]
+pub use crate::overtime::{overtime, OvertimeInfo, OvertimeKind};
+#[cfg(feature = "std")]
+pub use crate::paused::{is_paused, pauses};
+#[cfg(feature = "std")]
 pub use crate::players::players;
+#[cfg(feature = "std")]
 pub use crate::prints::prints;
+#[cfg(feature = "std")]
+pub use crate::race::race_runs;
+#[cfg(feature = "std")]
+pub use crate::recovery::{recover, repair};
+#[cfg(feature = "std")]
+pub use crate::redact::{redact, RedactOptions};
+#[cfg(feature = "std")]
+pub use crate::result::{result, MatchResult};
+#[cfg(feature = "std")]
+pub use crate::rounds::rounds;
+#[cfg(feature = "std")]
+pub use crate::scoreboard::scoreboard_timeline;
+#[cfg(feature = "std")]
 pub use crate::server::server;
+#[cfg(feature = "std")]
 pub use crate::serverinfo::{serverinfo, serverinfo_string, Settings};
+#[cfg(feature = "std")]
 pub use crate::teams::teams;
+#[cfg(feature = "std")]
 pub use crate::timestamp::timestamp;
+#[cfg(feature = "std")]
 pub use crate::validate::is_valid;
+#[cfg(feature = "std")]
+pub use crate::votes::{admin_events, vote_events};
 
 pub mod all {
+    #[cfg(feature = "std")]
     pub use crate::aborted::*;
+    #[cfg(feature = "std")]
+    pub use crate::batch::*;
+    #[cfg(feature = "std")]
     pub use crate::client::*;
+    #[cfg(feature = "std")]
     pub use crate::clientinfo::*;
+    #[cfg(feature = "std")]
     pub use crate::clients::*;
+    #[cfg(feature = "std")]
     pub use crate::duration::*;
+    pub use crate::error::Error;
+    #[cfg(feature = "std")]
+    pub use crate::fingerprint::*;
+    #[cfg(feature = "std")]
     pub use crate::flags::*;
+    #[cfg(feature = "std")]
     pub use crate::frags::*;
+    pub use crate::frames::*;
+    #[cfg(feature = "std")]
+    pub use crate::hoonymode::*;
+    #[cfg(feature = "std")]
     pub use crate::ktxstats::*;
+    #[cfg(feature = "std")]
     pub use crate::matchdate::*;
+    #[cfg(feature = "mmap")]
+    pub use crate::mmap::*;
+    #[cfg(feature = "std")]
+    pub use crate::network::*;
+    #[cfg(feature = "std")]
+    pub use crate::overtime::*;
+    #[cfg(feature = "std")]
+    pub use crate::paused::*;
+    #[cfg(feature = "std")]
     pub use crate::ping::*;
+    #[cfg(feature = "std")]
     pub use crate::player::*;
+    #[cfg(feature = "std")]
     pub use crate::players::*;
+    #[cfg(feature = "std")]
     pub use crate::prints::*;
+    #[cfg(feature = "std")]
+    pub use crate::race::*;
+    #[cfg(feature = "std")]
+    pub use crate::recovery::*;
+    #[cfg(feature = "std")]
+    pub use crate::redact::*;
+    #[cfg(feature = "std")]
+    pub use crate::result::*;
+    #[cfg(feature = "std")]
+    pub use crate::rounds::*;
+    #[cfg(feature = "std")]
+    pub use crate::scoreboard::*;
+    #[cfg(feature = "std")]
     pub use crate::server::*;
+    #[cfg(feature = "std")]
     pub use crate::serverinfo::*;
+    #[cfg(feature = "std")]
     pub use crate::team::*;
+    #[cfg(feature = "std")]
     pub use crate::teams::*;
+    #[cfg(feature = "std")]
     pub use crate::timestamp::*;
+    #[cfg(feature = "std")]
+    pub use crate::userinfo::*;
+    #[cfg(feature = "std")]
     pub use crate::validate::*;
+    #[cfg(feature = "std")]
+    pub use crate::votes::*;
 }
 
+#[cfg(feature = "std")]
 mod aborted;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
 mod bytesextra;
+#[cfg(feature = "capi")]
+mod capi;
+#[cfg(feature = "std")]
 mod client;
+#[cfg(feature = "std")]
 mod clientinfo;
+#[cfg(feature = "std")]
 mod clients;
+#[cfg(feature = "std")]
 mod duration;
+mod error;
+#[cfg(feature = "std")]
 mod filename;
+#[cfg(feature = "std")]
+mod fingerprint;
+#[cfg(feature = "std")]
 mod flags;
+#[cfg(feature = "std")]
 mod frags;
+mod frames;
+#[cfg(feature = "std")]
+mod hoonymode;
+#[cfg(feature = "std")]
 mod ktxstats;
+#[cfg(feature = "std")]
 mod matchdate;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "std")]
+mod network;
+#[cfg(feature = "std")]
+mod overtime;
+#[cfg(feature = "std")]
 mod paused;
+#[cfg(feature = "std")]
 mod ping;
+#[cfg(feature = "std")]
 mod player;
+#[cfg(feature = "std")]
 mod players;
+#[cfg(feature = "std")]
 mod prints;
+#[cfg(feature = "python")]
+mod python;
 mod qw;
+#[cfg(feature = "std")]
+mod race;
+#[cfg(feature = "std")]
+mod recovery;
+#[cfg(feature = "std")]
+mod redact;
+#[cfg(feature = "std")]
+mod result;
+#[cfg(feature = "std")]
+mod rounds;
+#[cfg(feature = "std")]
+mod scoreboard;
+#[cfg(feature = "std")]
 mod server;
+#[cfg(feature = "std")]
 mod serverinfo;
+#[cfg(all(test, feature = "serde"))]
+mod snapshots;
+#[cfg(feature = "std")]
 mod team;
+#[cfg(feature = "std")]
 mod teams;
+#[cfg(all(test, feature = "std"))]
+mod testdemo;
+#[cfg(feature = "std")]
 mod timestamp;
+#[cfg(feature = "std")]
 mod timezone;
+#[cfg(feature = "std")]
+mod userinfo;
+#[cfg(feature = "std")]
 mod validate;
+#[cfg(feature = "std")]
+mod votes;
+#[cfg(feature = "wasm")]
+mod wasm;
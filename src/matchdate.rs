@@ -1,4 +1,4 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 use bstr::ByteSlice;
 use chrono::{DateTime, Utc};
 
@@ -14,35 +14,42 @@ const MAX_LEN: usize = "yyyy-mm-dd hh:mm:ss abcde".len();
 pub fn matchdate(data: &[u8]) -> Result<DateTime<Utc>> {
     let raw_str = matchdate_string(data)?;
     let fixed_str = replace_tz_abbr_with_offset(&raw_str)?;
-    Ok(DateTime::parse_from_str(&fixed_str, "%Y-%m-%d %H:%M:%S%z")?.to_utc())
+    DateTime::parse_from_str(&fixed_str, "%Y-%m-%d %H:%M:%S%z")
+        .map(|dt| dt.to_utc())
+        .map_err(|_| Error::InvalidMatchdate)
 }
 
 fn replace_tz_abbr_with_offset(timestamp: &str) -> Result<String> {
-    let tz_abbr = &timestamp[DATETIME_LEN + 1..];
+    let (Some(datetime), Some(tz_abbr)) = (
+        timestamp.get(..DATETIME_LEN),
+        timestamp.get(DATETIME_LEN + 1..),
+    ) else {
+        return Err(Error::InvalidMatchdate);
+    };
     let Some(tz_offset) = timezone::utc_offset(tz_abbr) else {
-        return Err(e!("Invalid timezone abbreviation"));
+        return Err(Error::InvalidTimezone);
     };
-    Ok(format!("{}{}", &timestamp[..DATETIME_LEN], tz_offset))
+    Ok(format!("{}{}", datetime, tz_offset))
 }
 
 pub fn matchdate_string(data: &[u8]) -> Result<String> {
     let Some(mut index_from) = data.find(MATCHDATE_NEEDLE) else {
-        return Err(e!("Matchdate not found"));
+        return Err(Error::MissingMatchdate);
     };
     index_from += MATCHDATE_NEEDLE.len();
 
     let Some(mut index_to) = data[index_from..].find_byte(b'\n') else {
-        return Err(e!("Invalid matchdate"));
+        return Err(Error::InvalidMatchdate);
     };
     index_to += index_from;
 
     let length = index_to - index_from;
 
     if !(MIN_LEN..=MAX_LEN).contains(&length) {
-        return Err(e!("Invalid matchdate"));
+        return Err(Error::InvalidMatchdate);
     }
 
-    Ok(String::from_utf8(data[index_from..index_to].to_vec())?)
+    String::from_utf8(data[index_from..index_to].to_vec()).map_err(|_| Error::InvalidMatchdate)
 }
 
 #[cfg(test)]
@@ -93,39 +100,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_matchdate_invalid() {
+        let data = [
+            MATCHDATE_NEEDLE.as_slice(),
+            "2024-04-02 21:02:1\u{e5} CET\n".as_bytes(),
+        ]
+        .concat();
+        assert!(matches!(matchdate(&data), Err(Error::InvalidMatchdate)));
+    }
+
     #[test]
     fn test_matchdate_string() -> Result<()> {
         // invalid
-        assert_eq!(
-            matchdate_string(b"").unwrap_err().to_string(),
-            "Matchdate not found".to_string()
-        );
-        assert_eq!(
-            matchdate_string(b"foo").unwrap_err().to_string(),
-            "Matchdate not found".to_string()
-        );
-        assert_eq!(
-            matchdate_string(b"matchdate: foo").unwrap_err().to_string(),
-            "Invalid matchdate".to_string()
-        );
-        assert_eq!(
-            matchdate_string(b"matchdate: 2024")
-                .unwrap_err()
-                .to_string(),
-            "Invalid matchdate".to_string()
-        );
-        assert_eq!(
-            matchdate_string(b"matchdate: 2024-04-02 21:02:17\n")
-                .unwrap_err()
-                .to_string(),
-            "Invalid matchdate".to_string()
-        );
-        assert_eq!(
-            matchdate_string(b"matchdate: 2024-04-02 21:02:17 FOOBAR\n")
-                .unwrap_err()
-                .to_string(),
-            "Invalid matchdate".to_string()
-        );
+        assert!(matches!(
+            matchdate_string(b""),
+            Err(Error::MissingMatchdate)
+        ));
+        assert!(matches!(
+            matchdate_string(b"foo"),
+            Err(Error::MissingMatchdate)
+        ));
+        assert!(matches!(
+            matchdate_string(b"matchdate: foo"),
+            Err(Error::InvalidMatchdate)
+        ));
+        assert!(matches!(
+            matchdate_string(b"matchdate: 2024"),
+            Err(Error::InvalidMatchdate)
+        ));
+        assert!(matches!(
+            matchdate_string(b"matchdate: 2024-04-02 21:02:17\n"),
+            Err(Error::InvalidMatchdate)
+        ));
+        assert!(matches!(
+            matchdate_string(b"matchdate: 2024-04-02 21:02:17 FOOBAR\n"),
+            Err(Error::InvalidMatchdate)
+        ));
 
         // valid
         assert_eq!(
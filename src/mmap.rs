@@ -0,0 +1,40 @@
+use std::fs::File;
+use std::io::Result;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+/// Maps demo data from file instead of reading it into memory, use as `&[u8]`.
+///
+/// # Safety
+///
+/// The file must not be modified or truncated while the returned map is alive.
+/// Doing so is undefined behavior and may crash the process (e.g. SIGBUS).
+pub unsafe fn mmap_file(path: impl AsRef<Path>) -> Result<Mmap> {
+    let file = File::open(path)?;
+    Mmap::map(&file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::frames::frames;
+
+    use super::*;
+
+    #[test]
+    fn test_mmap_file() -> Result<()> {
+        let path = "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd";
+        let data = unsafe { mmap_file(path)? };
+
+        assert_eq!(&data[..], read(path)?.as_slice());
+        assert_eq!(frames(&data).count(), 29380);
+        assert!(unsafe { mmap_file("tests/files/missing.mvd") }.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+use crate::ping::is_ping_report;
+use crate::qw::frame;
+use crate::qw::message::{messages, Message};
+
+const LAG_SPIKE_FACTOR: u32 = 2; // times median ping
+const LAG_SPIKE_MIN_INCREASE: u32 = 50; // ms above median ping
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkSample {
+    pub time: Duration,
+    pub ping: u32,
+    pub packet_loss: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeriesStats {
+    pub min: u32,
+    pub max: u32,
+    pub avg: f32,
+    pub p95: u32,
+}
+
+impl From<&[u32]> for SeriesStats {
+    fn from(values: &[u32]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        let p95_rank = (sorted.len() as f32 * 0.95).ceil() as usize;
+
+        Self {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            avg: sorted.iter().sum::<u32>() as f32 / sorted.len() as f32,
+            p95: sorted[p95_rank.saturating_sub(1)],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LagSpike {
+    pub from: Duration,
+    pub to: Duration,
+    pub peak_ping: u32,
+    pub peak_packet_loss: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkStats {
+    pub number: u8,
+    pub samples: Vec<NetworkSample>,
+    pub ping: SeriesStats,
+    pub packet_loss: SeriesStats,
+    pub lag_spikes: Vec<LagSpike>,
+}
+
+impl NetworkStats {
+    pub fn from_samples(number: u8, samples: Vec<NetworkSample>) -> Self {
+        let pings: Vec<u32> = samples.iter().map(|s| s.ping).collect();
+        let packet_loss: Vec<u32> = samples.iter().map(|s| s.packet_loss).collect();
+
+        Self {
+            number,
+            ping: SeriesStats::from(pings.as_slice()),
+            packet_loss: SeriesStats::from(packet_loss.as_slice()),
+            lag_spikes: lag_spikes(&samples),
+            samples,
+        }
+    }
+}
+
+pub fn network_stats(data: &[u8]) -> Result<HashMap<u8, NetworkStats>> {
+    let samples = network_samples(data, None);
+
+    if samples.is_empty() {
+        return Err(Error::MissingPings);
+    }
+
+    Ok(samples
+        .into_iter()
+        .map(|(number, s)| (number, NetworkStats::from_samples(number, s)))
+        .collect())
+}
+
+// samples per player number from the periodic ping reports, optionally only the first reports
+pub fn network_samples(data: &[u8], max_reports: Option<usize>) -> HashMap<u8, Vec<NetworkSample>> {
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut report_count: usize = 0;
+    let mut samples: HashMap<u8, Vec<NetworkSample>> = HashMap::new();
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        if max_reports.is_some_and(|max| report_count >= max) {
+            break;
+        }
+
+        total_ms += info.duration;
+
+        if info.body_size > 0 && !info.is_hidden {
+            let messages = messages(&data[info.body_range.clone()]);
+
+            if is_ping_report(&messages) {
+                let time = Duration::from_millis(total_ms as u64);
+
+                for message in messages {
+                    match message {
+                        Message::UpdatePing(u) => {
+                            samples
+                                .entry(u.player_number)
+                                .or_default()
+                                .push(NetworkSample {
+                                    time,
+                                    ping: u.ping as u32,
+                                    packet_loss: 0,
+                                });
+                        }
+                        Message::UpdatePl(u) => {
+                            let last = samples
+                                .get_mut(&u.player_number)
+                                .and_then(|s| s.last_mut())
+                                .filter(|s| s.time == time);
+
+                            if let Some(sample) = last {
+                                sample.packet_loss = u.packet_loss as u32;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                report_count += 1;
+            }
+        }
+
+        index += info.size;
+    }
+
+    samples
+}
+
+pub fn lag_spikes(samples: &[NetworkSample]) -> Vec<LagSpike> {
+    if samples.is_empty() {
+        return vec![];
+    }
+
+    let mut pings: Vec<u32> = samples.iter().map(|s| s.ping).collect();
+    pings.sort_unstable();
+    let median = pings[pings.len() / 2];
+    let threshold = (median * LAG_SPIKE_FACTOR).max(median + LAG_SPIKE_MIN_INCREASE);
+
+    let mut spikes: Vec<LagSpike> = vec![];
+    let mut current: Option<LagSpike> = None;
+
+    for sample in samples {
+        if sample.ping < threshold {
+            if let Some(spike) = current.take() {
+                spikes.push(spike);
+            }
+            continue;
+        }
+
+        let spike = current.get_or_insert(LagSpike {
+            from: sample.time,
+            to: sample.time,
+            peak_ping: 0,
+            peak_packet_loss: 0,
+        });
+        spike.to = sample.time;
+        spike.peak_ping = spike.peak_ping.max(sample.ping);
+        spike.peak_packet_loss = spike.peak_packet_loss.max(sample.packet_loss);
+    }
+
+    if let Some(spike) = current {
+        spikes.push(spike);
+    }
+
+    spikes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn sample(secs: u64, ping: u32, packet_loss: u32) -> NetworkSample {
+        sample_ms(secs * 1000, ping, packet_loss)
+    }
+
+    fn sample_ms(ms: u64, ping: u32, packet_loss: u32) -> NetworkSample {
+        NetworkSample {
+            time: Duration::from_millis(ms),
+            ping,
+            packet_loss,
+        }
+    }
+
+    #[test]
+    fn test_network_stats() -> Result<()> {
+        {
+            let demo_data: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+            let err = network_stats(&demo_data).unwrap_err();
+            assert!(matches!(err, Error::MissingPings));
+        }
+
+        {
+            let demo_data = read("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")?;
+            let stats = network_stats(&demo_data)?;
+            assert_eq!(stats.len(), 3);
+
+            let kaboom = stats.get(&2).unwrap();
+            assert_eq!(kaboom.number, 2);
+            assert_eq!(kaboom.samples.len(), 59);
+            assert_eq!(kaboom.samples[0], sample_ms(3130, 29, 0));
+            assert_eq!(kaboom.samples[58], sample_ms(195725, 25, 0));
+            assert_eq!(kaboom.ping.min, 25);
+            assert_eq!(kaboom.ping.max, 50);
+            assert_eq!(kaboom.ping.p95, 28);
+            assert_eq!(kaboom.packet_loss, SeriesStats::default());
+            assert_eq!(kaboom.lag_spikes, vec![]);
+        }
+
+        {
+            let demo_data = read("tests/files/4on4_-s-_vs_pol[dm2]20241118-2135.mvd")?;
+            let stats = network_stats(&demo_data)?;
+            let lagger = stats.get(&2).unwrap();
+            assert_eq!(lagger.samples.len(), 121);
+            assert_eq!(lagger.ping.max, 580);
+            assert_eq!(lagger.ping.p95, 299);
+            assert_eq!(lagger.packet_loss.max, 8);
+            assert_eq!(
+                lagger.lag_spikes[0],
+                LagSpike {
+                    from: Duration::from_millis(362112),
+                    to: Duration::from_millis(377489),
+                    peak_ping: 580,
+                    peak_packet_loss: 7,
+                }
+            );
+            assert!(stats.get(&0).unwrap().lag_spikes.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_series_stats() {
+        assert_eq!(SeriesStats::from([].as_slice()), SeriesStats::default());
+        assert_eq!(
+            SeriesStats::from([12, 10, 14, 12].as_slice()),
+            SeriesStats {
+                min: 10,
+                max: 14,
+                avg: 12.0,
+                p95: 14,
+            }
+        );
+
+        let values: Vec<u32> = (1..=100).collect();
+        assert_eq!(SeriesStats::from(values.as_slice()).p95, 95);
+    }
+
+    #[test]
+    fn test_lag_spikes() {
+        assert_eq!(lag_spikes(&[]), vec![]);
+
+        let samples = vec![
+            sample(1, 25, 0),
+            sample(2, 26, 0),
+            sample(3, 90, 3),
+            sample(4, 140, 8),
+            sample(5, 25, 0),
+            sample(6, 24, 0),
+            sample(7, 200, 0),
+        ];
+
+        assert_eq!(
+            lag_spikes(&samples),
+            vec![
+                LagSpike {
+                    from: Duration::from_secs(3),
+                    to: Duration::from_secs(4),
+                    peak_ping: 140,
+                    peak_packet_loss: 8,
+                },
+                LagSpike {
+                    from: Duration::from_secs(7),
+                    to: Duration::from_secs(7),
+                    peak_ping: 200,
+                    peak_packet_loss: 0,
+                },
+            ]
+        );
+    }
+}
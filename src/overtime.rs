@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use quake_text::bytestr::to_ascii;
+
+use crate::prints::timed_prints;
+use crate::qw::prot::PrintId;
+use crate::scoreboard::scoreboard_timeline;
+use crate::userinfo::name_timeline;
+
+const MATCH_OVER: &str = "The match is over";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OvertimeKind {
+    Time,
+    SuddenDeath,
+    Tiebreak,
+}
+
+impl OvertimeKind {
+    // KTX overtime announcements (as ascii)
+    pub fn from_print(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+
+        if text.contains("sudden death") || text.contains("suddendeath") {
+            Some(OvertimeKind::SuddenDeath)
+        } else if text.contains("tie-break") || text.contains("tiebreak") {
+            Some(OvertimeKind::Tiebreak)
+        } else if text.contains("overtime") && text.contains("minute") {
+            Some(OvertimeKind::Time)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OvertimeInfo {
+    pub kind: OvertimeKind,
+    pub start: Duration,
+    pub duration: Option<Duration>, // none if the match did not end
+    pub regulation_frags: HashMap<String, i32>,
+    pub overtime_frags: HashMap<String, i32>,
+}
+
+pub fn overtime(data: &[u8]) -> Option<OvertimeInfo> {
+    let prints = timed_prints(data);
+    let (kind, start) = prints.iter().find_map(|p| {
+        if p.print.id != PrintId::High {
+            return None; // ignore chat
+        }
+        let kind = OvertimeKind::from_print(&to_ascii(&p.print.content))?;
+        Some((kind, p.time))
+    })?;
+    let end = prints
+        .iter()
+        .find(|p| p.time >= start && to_ascii(&p.print.content).starts_with(MATCH_OVER))
+        .map(|p| p.time);
+
+    let names = name_timeline(data).last_names();
+    let scoreboard = scoreboard_timeline(data);
+    let regulation = scoreboard.frags_at(start);
+    let total = match end {
+        Some(end) => scoreboard.frags_at(end),
+        None => scoreboard.final_frags(),
+    };
+
+    let mut regulation_frags: HashMap<String, i32> = HashMap::new();
+    let mut overtime_frags: HashMap<String, i32> = HashMap::new();
+
+    for (number, name) in names {
+        let before = regulation.get(&number).copied().unwrap_or_default();
+        let after = total.get(&number).copied().unwrap_or(before);
+        regulation_frags.insert(name.clone(), before);
+        overtime_frags.insert(name, after - before);
+    }
+
+    Some(OvertimeInfo {
+        kind,
+        start,
+        duration: end.map(|e| e - start),
+        regulation_frags,
+        overtime_frags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testdemo::{print, to_all};
+
+    #[test]
+    fn test_overtime_kind_from_print() {
+        assert_eq!(
+            OvertimeKind::from_print("Overtime! 3 minutes overtime follows_"),
+            Some(OvertimeKind::Time)
+        );
+        assert_eq!(
+            OvertimeKind::from_print("Suddendeath overtime in progress_"),
+            Some(OvertimeKind::SuddenDeath)
+        );
+        assert_eq!(
+            OvertimeKind::from_print("Tie-break overtime!_"),
+            Some(OvertimeKind::Tiebreak)
+        );
+        assert_eq!(OvertimeKind::from_print("The match is over_"), None);
+    }
+
+    #[test]
+    fn test_overtime() -> Result<()> {
+        assert_eq!(
+            overtime(&read(
+                "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd"
+            )?),
+            None
+        );
+        assert_eq!(
+            overtime(&read(
+                "tests/files/2on2_sf_vs_red[frobodm2]220104-0915.mvd"
+            )?),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_overtime_synthetic() {
+        let userinfo = |slot: u8, name: &[u8]| {
+            [
+                &[0x28, slot, 0, 0, 0, 0],
+                b"\\name\\".as_slice(),
+                name,
+                &[0],
+            ]
+            .concat()
+        };
+        let demo = |announcement: &[u8]| {
+            [
+                to_all(0, &[userinfo(0, b"FOO"), userinfo(1, b"BAR")].concat()),
+                to_all(100, &[14, 0, 5, 0]),
+                to_all(100, &[14, 1, 5, 0]),
+                to_all(100, &print(2, announcement)),
+                to_all(100, &[14, 0, 7, 0]),
+                to_all(100, &[14, 1, 6, 0]),
+                to_all(100, &print(2, b"The match is over\n")),
+            ]
+            .concat()
+        };
+        let frags = |foo: i32, bar: i32| {
+            HashMap::from([("FOO".to_string(), foo), ("BAR".to_string(), bar)])
+        };
+
+        assert_eq!(
+            overtime(&demo(b"Overtime! 3 minutes overtime follows\n")),
+            Some(OvertimeInfo {
+                kind: OvertimeKind::Time,
+                start: Duration::from_millis(300),
+                duration: Some(Duration::from_millis(300)),
+                regulation_frags: frags(5, 5),
+                overtime_frags: frags(2, 1),
+            })
+        );
+        assert_eq!(
+            overtime(&demo(b"Suddendeath overtime in progress\n")).map(|o| o.kind),
+            Some(OvertimeKind::SuddenDeath)
+        );
+    }
+}
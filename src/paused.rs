@@ -1,6 +1,37 @@
-use crate::validate::has_end_of_demo_print;
+use std::time::Duration;
+
 use bstr::ByteSlice;
 
+use crate::qw::frame;
+use crate::qw::message::{messages, Message};
+use crate::qw::primitives::ByteReader;
+use crate::qw::prot::{HiddenMessage, PrintId};
+use crate::validate::has_end_of_demo_print;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pause {
+    pub index: usize,
+    pub time: Duration, // game time, does not advance while paused
+    pub real_duration: Option<Duration>, // only known if demo contains paused duration messages
+    pub paused_by: Option<String>,
+    pub unpaused_by: Option<String>,
+    pub is_ongoing: bool, // demo ended while paused
+}
+
+impl Pause {
+    fn new(index: usize, time: Duration) -> Self {
+        Self {
+            index,
+            time,
+            real_duration: None,
+            paused_by: None,
+            unpaused_by: None,
+            is_ongoing: true,
+        }
+    }
+}
+
 pub fn is_paused(data: &[u8]) -> bool {
     if has_end_of_demo_print(data) {
         return false;
@@ -20,6 +51,101 @@ pub fn is_paused(data: &[u8]) -> bool {
     data.rfind(IS_PAUSED_NEEDLE).is_some() || data.rfind(PAUSED_THE_GAME_NEEDLE).is_some()
 }
 
+pub fn pauses(data: &[u8]) -> Vec<Pause> {
+    const PAUSED_SUFFIX: &str = " paused the game";
+    const UNPAUSED_SUFFIX: &str = " unpaused the game";
+
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut pauses: Vec<Pause> = vec![];
+    let mut current: Option<Pause> = None;
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        // game time advancing means the game is no longer paused
+        if info.duration > 0 {
+            if let Some(mut pause) = current.take() {
+                pause.is_ongoing = false;
+                pauses.push(pause);
+            }
+        }
+
+        total_ms += info.duration;
+        let time = Duration::from_millis(total_ms as u64);
+        let body = &data[info.body_range.clone()];
+
+        if info.is_hidden {
+            if let Some(pause) = current.as_mut() {
+                let elapsed = paused_duration(body);
+
+                if elapsed > Duration::ZERO {
+                    pause.real_duration = Some(pause.real_duration.unwrap_or_default() + elapsed);
+                }
+            }
+        } else if info.body_size > 0 {
+            for message in messages(body) {
+                match message {
+                    Message::SetPause(true) => {
+                        current.get_or_insert_with(|| Pause::new(info.index, time));
+                    }
+                    Message::SetPause(false) => {
+                        if let Some(mut pause) = current.take() {
+                            pause.is_ongoing = false;
+                            pauses.push(pause);
+                        }
+                    }
+                    // server prints only, chat can contain the same text
+                    Message::Print(p) if p.id == PrintId::High => {
+                        let content = quake_text::bytestr::to_unicode(p.content.trim_end());
+
+                        if let Some(name) = strip_sentence_suffix(&content, UNPAUSED_SUFFIX) {
+                            if let Some(pause) = current.as_mut() {
+                                pause.unpaused_by = Some(name.to_string());
+                            }
+                        } else if let Some(name) = strip_sentence_suffix(&content, PAUSED_SUFFIX) {
+                            let pause = current.get_or_insert_with(|| Pause::new(info.index, time));
+                            pause.paused_by = Some(name.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        index += info.size;
+    }
+
+    pauses.extend(current);
+    pauses
+}
+
+// sum of paused duration messages within hidden frame body: <u32 size><u16 type><byte msec>
+fn paused_duration(body: &[u8]) -> Duration {
+    let mut reader = ByteReader::new(body);
+    let mut total_ms: u64 = 0;
+
+    while let (Some(size), Some(hidden_type)) = (reader.u32(), reader.u16()) {
+        if HiddenMessage::from(&hidden_type) == HiddenMessage::PausedDuration {
+            if let Some(msec) = body.get(reader.position()) {
+                total_ms += *msec as u64;
+            }
+        }
+
+        if reader.skip(size as usize).is_none() {
+            break;
+        }
+    }
+
+    Duration::from_millis(total_ms)
+}
+
+// "{name}<suffix>" or "{name}<suffix>. <more sentences>", e.g. remaining pause requests
+fn strip_sentence_suffix<'a>(text: &'a str, suffix: &str) -> Option<&'a str> {
+    text.match_indices(suffix)
+        .map(|(i, _)| (&text[..i], &text[i + suffix.len()..]))
+        .find(|(_, rest)| rest.is_empty() || *rest == "." || rest.starts_with(". "))
+        .map(|(name, _)| name)
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::read;
@@ -27,6 +153,7 @@ mod tests {
     use anyhow::Result;
 
     use super::*;
+    use crate::testdemo::{print, to_all};
 
     #[test]
     fn test_is_paused() -> Result<()> {
@@ -57,4 +184,83 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_pauses() -> Result<()> {
+        assert_eq!(
+            pauses(&read(
+                "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd"
+            )?),
+            vec![]
+        );
+        assert_eq!(
+            pauses(&read("tests/files/4on4_-s-_vs_pol[dm2]20241118-2135.mvd")?),
+            vec![Pause {
+                index: 2149795,
+                time: Duration::from_millis(377489),
+                real_duration: None,
+                paused_by: Some("maCÌåò".to_string()),
+                unpaused_by: None,
+                is_ongoing: true,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pauses_from_prints() {
+        let data = [
+            to_all(100, &print(3, b"FOO: BAR paused the game\n")),
+            to_all(
+                0,
+                &[[24, 1].as_slice(), &print(2, b"BAR paused the game\n")].concat(),
+            ),
+            to_all(0, &print(3, b"FOO: BAR unpaused the game\n")),
+            to_all(
+                0,
+                &[print(2, b"BAZ unpaused the game\n"), vec![24, 0]].concat(),
+            ),
+            to_all(100, &[]),
+        ]
+        .concat();
+
+        assert_eq!(
+            pauses(&data),
+            vec![Pause {
+                index: 34,
+                time: Duration::from_millis(100),
+                real_duration: None,
+                paused_by: Some("BAR".to_string()),
+                unpaused_by: Some("BAZ".to_string()),
+                is_ongoing: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_strip_sentence_suffix() {
+        let suffix = " paused the game";
+        assert_eq!(
+            strip_sentence_suffix("FOO paused the game", suffix),
+            Some("FOO")
+        );
+        assert_eq!(
+            strip_sentence_suffix(
+                "FOO paused the game. He has 2 remaining request(s).",
+                suffix
+            ),
+            Some("FOO")
+        );
+        assert_eq!(
+            strip_sentence_suffix("FOO paused the game again", suffix),
+            None
+        );
+    }
+
+    #[test]
+    fn test_paused_duration() {
+        let body: &[u8] = &[1, 0, 0, 0, 0x0A, 0, 13, 1, 0, 0, 0, 0x0A, 0, 7];
+        assert_eq!(paused_duration(body), Duration::from_millis(20));
+        assert_eq!(paused_duration(&[]), Duration::ZERO);
+    }
 }
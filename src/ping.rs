@@ -1,64 +1,40 @@
 use std::collections::HashMap;
-use std::io::Cursor;
 
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 
-use crate::qw::frame;
-use crate::qw::message::message_type::ReadMessageType;
-use crate::qw::message::update_ping::ReadUpdatePing;
-use crate::qw::prot::MessageType;
+use crate::network::network_samples;
+use crate::qw::message::Message;
 
-pub fn ping_per_player_number(data: &[u8]) -> Result<HashMap<u8, u32>> {
-    let max_samples: usize = 8;
-    let mut sample_count: usize = 0;
-    let mut index = 0;
-    let mut total_pings: HashMap<u8, Vec<u16>> = HashMap::new();
-
-    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
-        if info.body_size > 0 {
-            let mut body = Cursor::new(&data[info.body_range.clone()]);
-            let mut did_ping_update = false;
-
-            while body
-                .read_message_type()
-                .is_ok_and(|t| t == MessageType::UpdatePing)
-            {
-                if let Ok(u) = body.read_update_ping() {
-                    let pings = total_pings.entry(u.player_number).or_default();
-                    pings.push(u.ping);
-
-                    body.set_position(body.position() + 2); // skip UpdatePl
-                    did_ping_update = true;
-                }
-            }
-
-            if did_ping_update {
-                sample_count += 1;
-            }
-        }
-
-        if sample_count >= max_samples {
-            break;
-        }
+// the first reports are enough for a scoreboard ping and reading stops early,
+// see network_stats for ping over the whole demo
+const MAX_REPORTS: usize = 8;
 
-        index += info.size;
-    }
+pub fn ping_per_player_number(data: &[u8]) -> Result<HashMap<u8, u32>> {
+    let samples = network_samples(data, Some(MAX_REPORTS));
 
-    if total_pings.is_empty() {
-        return Err(e!("Unable to read pings"));
+    if samples.is_empty() {
+        return Err(Error::MissingPings);
     }
 
     let mut average_ping: HashMap<u8, u32> = HashMap::new();
 
-    for (pnum, pings) in total_pings.iter() {
-        let pings_sum = pings.iter().sum::<u16>();
-        let avg_ping = (pings_sum as f32 / pings.len() as f32) as u32;
+    for (pnum, samples) in samples.iter() {
+        let pings_sum = samples.iter().map(|s| s.ping).sum::<u32>();
+        let avg_ping = (pings_sum as f32 / samples.len() as f32) as u32;
         average_ping.insert(*pnum, avg_ping);
     }
 
     Ok(average_ping)
 }
 
+// periodic ping reports only contain ping and packet loss updates
+pub fn is_ping_report(messages: &[Message]) -> bool {
+    !messages.is_empty()
+        && messages
+            .iter()
+            .all(|m| matches!(m, Message::UpdatePing(_) | Message::UpdatePl(_)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::read;
@@ -73,7 +49,7 @@ mod tests {
         {
             let demo_data: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
             let err = ping_per_player_number(&demo_data).unwrap_err();
-            assert_eq!(err.to_string(), "Unable to read pings".to_string());
+            assert!(matches!(err, Error::MissingPings));
         }
 
         {
@@ -3,6 +3,7 @@ use std::cmp::Ordering;
 const BOT_PING: i32 = 10;
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     pub name: String,
     pub team: String,
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
 
-use anyhow::Result;
+use crate::error::Result;
 use ktxstats::v3::KtxstatsV3;
 
 use crate::clients::clients;
-use crate::frags::frags_per_player_name;
+use crate::frags::frags_per_player_number;
 use crate::ktxstats::ktxstats_v3;
 use crate::ping::ping_per_player_number;
 use crate::player;
@@ -27,7 +27,7 @@ pub fn players_from_ktxstats(stats: &KtxstatsV3) -> Result<Vec<Player>> {
 pub fn players_from_parsing(data: &[u8]) -> Result<Vec<Player>> {
     let clients = clients(data)?;
     let pings = ping_per_player_number(data)?;
-    let frags = frags_per_player_name(data);
+    let frags = frags_per_player_number(data);
     let mut pmap: HashMap<u8, Player> = HashMap::new();
 
     for c in clients.iter().filter(|c| !c.is_spectator) {
@@ -35,7 +35,7 @@ pub fn players_from_parsing(data: &[u8]) -> Result<Vec<Player>> {
             name: c.name.clone(),
             team: c.team.clone(),
             color: c.color,
-            frags: *frags.get(&c.name).unwrap_or(&0),
+            frags: *frags.get(&c.number).unwrap_or(&0),
             ping: *pings.get(&c.number).unwrap_or(&0),
             auth_username: c.auth_username.clone(),
             auth_cc: c.auth_cc.clone(),
@@ -150,7 +150,7 @@ mod tests {
                         name: "> MrJustice".to_string(),
                         team: "=SF=".to_string(),
                         color: [0, 4],
-                        frags: -5,
+                        frags: -6,
                         ping: 10,
                         auth_username: None,
                         auth_cc: None,
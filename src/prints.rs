@@ -1,10 +1,9 @@
-use std::io::Cursor;
+use std::time::Duration;
+
+pub use crate::qw::message::Print;
 
 use crate::qw::frame;
-use crate::qw::message::message_type::ReadMessageType;
-use crate::qw::message::print::ReadPrint;
-use crate::qw::message::Print;
-use crate::qw::prot::MessageType;
+use crate::qw::message::{message_refs, messages, Message, MessageRef};
 
 pub fn prints(data: &[u8]) -> Vec<Print> {
     let mut index = 0;
@@ -12,16 +11,13 @@ pub fn prints(data: &[u8]) -> Vec<Print> {
 
     while let Ok(info) = frame::Info::from_data_and_index(data, index) {
         if info.body_size > 0 {
-            let mut body = Cursor::new(&data[info.body_range]);
-
-            while body
-                .read_message_type()
-                .is_ok_and(|t| t == MessageType::Print)
-            {
-                if let Ok(print) = body.read_print() {
-                    if !print.content.is_empty() {
-                        prints.push(print);
-                    }
+            // leading prints of the body
+            for message in message_refs(&data[info.body_range]) {
+                let MessageRef::Print(print) = message else {
+                    break;
+                };
+                if !print.content.is_empty() {
+                    prints.push(print.to_print());
                 }
             }
         }
@@ -33,6 +29,55 @@ pub fn prints(data: &[u8]) -> Vec<Print> {
     prints
 }
 
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedPrint {
+    pub index: usize,
+    pub time: Duration,
+    pub print: Print,
+}
+
+// prints of all frames with game time, consecutive duplicates (same print to several clients) removed
+pub fn timed_prints(data: &[u8]) -> Vec<TimedPrint> {
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut prints: Vec<TimedPrint> = Vec::new();
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += info.duration;
+
+        if info.body_size > 0 && !info.is_hidden {
+            let time = Duration::from_millis(total_ms as u64);
+
+            for message in messages(&data[info.body_range.clone()]) {
+                let Message::Print(print) = message else {
+                    continue;
+                };
+
+                if print.content.is_empty() {
+                    continue;
+                }
+
+                let is_duplicate = prints
+                    .last()
+                    .is_some_and(|p| p.time == time && p.print == print);
+
+                if !is_duplicate {
+                    prints.push(TimedPrint {
+                        index: info.index,
+                        time,
+                        print,
+                    });
+                }
+            }
+        }
+
+        index += info.size;
+    }
+
+    prints
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::read;
@@ -40,6 +85,8 @@ mod tests {
     use anyhow::Result;
     use pretty_assertions::assert_eq;
 
+    use quake_text::bytestr::to_ascii;
+
     use super::*;
 
     #[test]
@@ -63,4 +110,19 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_timed_prints() -> Result<()> {
+        let demo_data = read("tests/files/ffa_5[dm4]20240501-1229.mvd")?;
+        let prints = timed_prints(&demo_data);
+        let stopped = prints
+            .iter()
+            .find(|p| to_ascii(&p.print.content) == "test votes for stopping the match_")
+            .unwrap();
+
+        assert_eq!(stopped.index, 532767);
+        assert_eq!(stopped.time, Duration::from_millis(71503));
+
+        Ok(())
+    }
 }
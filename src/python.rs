@@ -0,0 +1,308 @@
+use std::fs::read;
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use quake_text::bytestr::to_unicode;
+
+use crate::duration::match_duration;
+use crate::error::Error;
+use crate::qw::prot::PrintId;
+use crate::serverinfo::serverinfo;
+use crate::userinfo::name_timeline;
+
+impl From<Error> for PyErr {
+    fn from(err: Error) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+#[pyclass(
+    name = "Player",
+    module = "mvdparser",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyPlayer {
+    name: String,
+    team: String,
+    color: (u8, u8),
+    frags: i32,
+    ping: u32,
+    is_bot: bool,
+    auth_username: Option<String>,
+    auth_cc: Option<String>,
+}
+
+#[pymethods]
+impl PyPlayer {
+    fn __repr__(&self) -> String {
+        format!(
+            "Player(name={:?}, team={:?}, frags={})",
+            self.name, self.team, self.frags
+        )
+    }
+}
+
+impl From<&crate::player::Player> for PyPlayer {
+    fn from(player: &crate::player::Player) -> Self {
+        Self {
+            name: player.name.clone(),
+            team: player.team.clone(),
+            color: (player.color[0], player.color[1]),
+            frags: player.frags,
+            ping: player.ping,
+            is_bot: player.is_bot,
+            auth_username: player.auth_username.clone(),
+            auth_cc: player.auth_cc.clone(),
+        }
+    }
+}
+
+#[pyclass(
+    name = "Team",
+    module = "mvdparser",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyTeam {
+    name: String,
+    color: (u8, u8),
+    frags: i32,
+    ping: u32,
+    players: Vec<PyPlayer>,
+}
+
+#[pymethods]
+impl PyTeam {
+    fn __repr__(&self) -> String {
+        format!("Team(name={:?}, frags={})", self.name, self.frags)
+    }
+}
+
+impl From<&crate::team::Team> for PyTeam {
+    fn from(team: &crate::team::Team) -> Self {
+        Self {
+            name: team.name.clone(),
+            color: (team.color[0], team.color[1]),
+            frags: team.frags,
+            ping: team.ping,
+            players: team.players.iter().map(PyPlayer::from).collect(),
+        }
+    }
+}
+
+#[pyclass(
+    name = "FragUpdate",
+    module = "mvdparser",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyFragUpdate {
+    time: f64, // seconds
+    number: u8,
+    name: Option<String>, // None if not a player, e.g. a spectator
+    frags: i32,
+}
+
+#[pymethods]
+impl PyFragUpdate {
+    fn __repr__(&self) -> String {
+        format!(
+            "FragUpdate(time={}, name={}, frags={})",
+            self.time,
+            self.name
+                .as_ref()
+                .map_or("None".to_string(), |n| format!("{:?}", n)),
+            self.frags
+        )
+    }
+}
+
+#[pyclass(
+    name = "Print",
+    module = "mvdparser",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PyPrint {
+    time: f64, // seconds
+    level: &'static str,
+    content: String, // quake text as unicode
+}
+
+#[pymethods]
+impl PyPrint {
+    fn __repr__(&self) -> String {
+        format!(
+            "Print(time={}, level={:?}, content={:?})",
+            self.time, self.level, self.content
+        )
+    }
+}
+
+impl From<&crate::prints::TimedPrint> for PyPrint {
+    fn from(print: &crate::prints::TimedPrint) -> Self {
+        Self {
+            time: print.time.as_secs_f64(),
+            level: match print.print.id {
+                PrintId::Low => "low",
+                PrintId::Medium => "medium",
+                PrintId::High => "high",
+                PrintId::Chat => "chat",
+                PrintId::Unknown => "unknown",
+            },
+            content: to_unicode(&print.print.content),
+        }
+    }
+}
+
+#[pyclass(name = "Demo", module = "mvdparser", frozen)]
+pub struct PyDemo {
+    data: Vec<u8>,
+}
+
+#[pymethods]
+impl PyDemo {
+    #[new]
+    fn new(data: &[u8]) -> Self {
+        Self {
+            data: data.to_vec(),
+        }
+    }
+
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        let data = read(&path).map_err(|err| PyOSError::new_err(err.to_string()))?;
+        Ok(Self { data })
+    }
+
+    #[getter]
+    fn map(&self) -> Option<String> {
+        serverinfo(&self.data).ok().and_then(|s| s.map)
+    }
+
+    #[getter]
+    fn mode(&self) -> Option<String> {
+        serverinfo(&self.data).ok().and_then(|s| s.mode)
+    }
+
+    #[getter]
+    fn hostname(&self) -> Option<String> {
+        serverinfo(&self.data).ok().and_then(|s| s.hostname)
+    }
+
+    #[getter]
+    fn duration(&self) -> Option<f64> {
+        match_duration(&self.data).ok().map(|d| d.as_secs_f64())
+    }
+
+    fn players(&self) -> PyResult<Vec<PyPlayer>> {
+        players(&self.data)
+    }
+
+    fn teams(&self) -> PyResult<Vec<PyTeam>> {
+        teams(&self.data)
+    }
+
+    fn frag_timeline(&self) -> PyResult<Vec<PyFragUpdate>> {
+        frag_timeline(&self.data)
+    }
+
+    fn prints(&self) -> Vec<PyPrint> {
+        prints(&self.data)
+    }
+
+    fn __len__(&self) -> usize {
+        self.data.len()
+    }
+}
+
+#[pyfunction]
+fn players(data: &[u8]) -> PyResult<Vec<PyPlayer>> {
+    let players = crate::players::players(data)?;
+    Ok(players.iter().map(PyPlayer::from).collect())
+}
+
+#[pyfunction]
+fn teams(data: &[u8]) -> PyResult<Vec<PyTeam>> {
+    let teams = crate::teams::teams(data)?;
+    Ok(teams.iter().map(PyTeam::from).collect())
+}
+
+#[pyfunction]
+fn frag_timeline(data: &[u8]) -> PyResult<Vec<PyFragUpdate>> {
+    let names = name_timeline(data);
+    let updates = crate::scoreboard::scoreboard_timeline(data).updates;
+
+    Ok(updates
+        .iter()
+        .map(|u| PyFragUpdate {
+            time: u.time.as_secs_f64(),
+            number: u.number,
+            name: names.names_at(u.index).remove(&u.number),
+            frags: u.frags,
+        })
+        .collect())
+}
+
+#[pyfunction]
+fn prints(data: &[u8]) -> Vec<PyPrint> {
+    crate::prints::timed_prints(data)
+        .iter()
+        .map(PyPrint::from)
+        .collect()
+}
+
+#[pymodule]
+fn mvdparser(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDemo>()?;
+    m.add_class::<PyPlayer>()?;
+    m.add_class::<PyTeam>()?;
+    m.add_class::<PyFragUpdate>()?;
+    m.add_class::<PyPrint>()?;
+    m.add_function(wrap_pyfunction!(players, m)?)?;
+    m.add_function(wrap_pyfunction!(teams, m)?)?;
+    m.add_function(wrap_pyfunction!(frag_timeline, m)?)?;
+    m.add_function(wrap_pyfunction!(prints, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testdemo::to_all;
+
+    #[test]
+    fn test_frag_timeline_after_rename() {
+        let userinfo = |name: &[u8]| [b"\x28\x00\x01\x00\x00\x00\\name\\", name, &[0]].concat();
+        let data = [
+            to_all(0, &userinfo(b"FOO")),
+            to_all(100, &[14, 0, 1, 0]),
+            to_all(100, &userinfo(b"BAR")),
+            to_all(100, &[14, 0, 2, 0]),
+            to_all(100, &[14, 1, 1, 0]),
+        ]
+        .concat();
+
+        let names: Vec<Option<String>> = frag_timeline(&data)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![Some("FOO".to_string()), Some("BAR".to_string()), None]
+        );
+    }
+}
@@ -1,6 +1,5 @@
-use std::io::Cursor;
-
-use crate::qw::primitives::{numsize, ReadPrimitives};
+use crate::error::Error;
+use crate::qw::primitives::{numsize, ByteReader};
 use crate::qw::prot::HiddenMessage;
 
 pub const HEADER_SIZE: usize = numsize::LONG + 2 * numsize::SHORT;
@@ -14,18 +13,26 @@ pub struct Info {
 }
 
 impl TryFrom<&[u8]> for Info {
-    type Error = anyhow::Error;
+    type Error = Error;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        let mut cur = Cursor::new(value);
+        let mut reader = ByteReader::new(value);
+        let (Some(size), Some(hidden_type), Some(number)) =
+            (reader.u32(), reader.u16(), reader.u16())
+        else {
+            return Err(Error::InvalidBlock);
+        };
+
         // exclude block number bytes from size
-        let body_size = cur.read_u32()? as usize - numsize::SHORT;
+        let Some(body_size) = (size as usize).checked_sub(numsize::SHORT) else {
+            return Err(Error::InvalidBlock);
+        };
 
         Ok(Info {
             body_size,
             total_size: HEADER_SIZE + body_size,
-            hidden_message: HiddenMessage::from(&cur.read_u16()?),
-            number: cur.read_u16()? as usize,
+            hidden_message: HiddenMessage::from(&hidden_type),
+            number: number as usize,
         })
     }
 }
@@ -65,4 +72,10 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_frameinfo_invalid() {
+        assert!(Info::try_from([].as_slice()).is_err());
+        assert!(Info::try_from([1, 0, 0, 0, 3, 0, 0, 0].as_slice()).is_err());
+    }
 }
@@ -1,22 +1,75 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 
 use crate::qw::flagprint;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlagColor {
+    Red,
+    Blue,
+}
+
+impl FlagColor {
+    pub fn opposite(&self) -> Self {
+        match self {
+            FlagColor::Red => FlagColor::Blue,
+            FlagColor::Blue => FlagColor::Red,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlagColor::Red => "red",
+            FlagColor::Blue => "blue",
+        }
+    }
+
+    // bottom color of the team in ktx ctf
+    pub fn bottom_color(&self) -> u8 {
+        match self {
+            FlagColor::Red => 4,
+            FlagColor::Blue => 13,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FlagEvent {
     CapturedFlag { player: String },
     GotFlag { player: String },
+    LostFlag { player: String },
     DefendsFlag { player: String },
     DefendsFlagCarrier { player: String },
     DefendsFlagCarrierVsAggressive { player: String },
     ReturnedFlag { player: String },
     ReturnedFlagAssist { player: String },
+    FlagReturned, // returned automatically
 }
 
-impl TryFrom<&str> for FlagEvent {
-    type Error = anyhow::Error;
+impl FlagEvent {
+    pub fn player(&self) -> Option<&str> {
+        match self {
+            FlagEvent::CapturedFlag { player }
+            | FlagEvent::GotFlag { player }
+            | FlagEvent::LostFlag { player }
+            | FlagEvent::DefendsFlag { player }
+            | FlagEvent::DefendsFlagCarrier { player }
+            | FlagEvent::DefendsFlagCarrierVsAggressive { player }
+            | FlagEvent::ReturnedFlag { player }
+            | FlagEvent::ReturnedFlagAssist { player } => Some(player),
+            FlagEvent::FlagReturned => None,
+        }
+    }
+
+    // parses event and color of the flag involved, if known
+    pub fn with_flag(value: &str) -> Result<(Self, Option<FlagColor>)> {
+        const COLORS: [FlagColor; 2] = [FlagColor::Red, FlagColor::Blue];
+
+        if let Some(pos) = flagprint::FLAG_RETURNED.iter().position(|n| value == *n) {
+            return Ok((FlagEvent::FlagReturned, Some(COLORS[pos])));
+        }
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let print_types: Vec<(usize, Vec<&str>)> = vec![
             (1, flagprint::X_CAPTURED_FLAG.to_vec()),
             (2, flagprint::X_DEFENDS_FLAG.to_vec()),
@@ -25,26 +78,53 @@ impl TryFrom<&str> for FlagEvent {
             (5, flagprint::X_GOT_FLAG.to_vec()),
             (6, flagprint::X_RETURNED_FLAG.to_vec()),
             (7, flagprint::X_FRAGS_CARRIER.to_vec()),
+            (8, flagprint::X_LOST_FLAG.to_vec()),
         ];
 
         for (index, needles) in print_types {
-            if let Some(pos) = needles.iter().find_map(|n| value.find(n)) {
+            let found = needles
+                .iter()
+                .enumerate()
+                .find_map(|(n_index, n)| value.find(n).map(|pos| (n_index, pos)));
+
+            if let Some((n_index, pos)) = found {
                 let player = value[..pos].to_string();
+                let flag = COLORS[n_index];
 
+                // "defends RED's flag carrier" (carrying the blue flag)
                 match index {
-                    1 => return Ok(FlagEvent::CapturedFlag { player }),
-                    2 => return Ok(FlagEvent::DefendsFlag { player }),
-                    3 => return Ok(FlagEvent::DefendsFlagCarrierVsAggressive { player }),
-                    4 => return Ok(FlagEvent::DefendsFlagCarrier { player }),
-                    5 => return Ok(FlagEvent::GotFlag { player }),
-                    6 => return Ok(FlagEvent::ReturnedFlag { player }),
-                    7 => return Ok(FlagEvent::ReturnedFlagAssist { player }),
+                    1 => return Ok((FlagEvent::CapturedFlag { player }, Some(flag))),
+                    2 => return Ok((FlagEvent::DefendsFlag { player }, Some(flag))),
+                    3 => {
+                        return Ok((
+                            FlagEvent::DefendsFlagCarrierVsAggressive { player },
+                            Some(flag.opposite()),
+                        ))
+                    }
+                    4 => {
+                        return Ok((
+                            FlagEvent::DefendsFlagCarrier { player },
+                            Some(flag.opposite()),
+                        ))
+                    }
+                    5 => return Ok((FlagEvent::GotFlag { player }, Some(flag))),
+                    6 => return Ok((FlagEvent::ReturnedFlag { player }, Some(flag))),
+                    7 => return Ok((FlagEvent::ReturnedFlagAssist { player }, None)),
+                    8 => return Ok((FlagEvent::LostFlag { player }, Some(flag))),
                     _ => {}
                 }
             }
         }
 
-        Err(e!(r#"Unable to parse as flag event: "{}""#, value))
+        Err(Error::UnknownPrint(value.to_string()))
+    }
+}
+
+impl TryFrom<&str> for FlagEvent {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::with_flag(value).map(|(event, _)| event)
     }
 }
 
@@ -138,6 +218,16 @@ mod tests {
                     player: "FOO".to_string(),
                 }),
             ),
+            (
+                "FOO ìïóô the ÂÌÕÅ flag!\n",
+                Ok(FlagEvent::LostFlag {
+                    player: "FOO".to_string(),
+                }),
+            ),
+            (
+                "The ÒÅÄ flag has been returned\n",
+                Ok(FlagEvent::FlagReturned),
+            ),
         ]);
 
         for (input, expected) in test_cases {
@@ -147,4 +237,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_with_flag() -> Result<()> {
+        assert_eq!(
+            FlagEvent::with_flag("FOO ãáðôõòåä the ÂÌÕÅ flag!\n")?,
+            (
+                FlagEvent::CapturedFlag {
+                    player: "FOO".to_string()
+                },
+                Some(FlagColor::Blue)
+            )
+        );
+        assert_eq!(
+            FlagEvent::with_flag("FOO defends ÒÅÄ's flag carrier\n")?,
+            (
+                FlagEvent::DefendsFlagCarrier {
+                    player: "FOO".to_string()
+                },
+                Some(FlagColor::Blue)
+            )
+        );
+        assert_eq!(
+            FlagEvent::with_flag("FOO gets an assist for fragging the flag carrier!\n")?,
+            (
+                FlagEvent::ReturnedFlagAssist {
+                    player: "FOO".to_string()
+                },
+                None
+            )
+        );
+        assert_eq!(
+            FlagEvent::with_flag("The ÂÌÕÅ flag has been returned\n")?,
+            (FlagEvent::FlagReturned, Some(FlagColor::Blue))
+        );
+
+        Ok(())
+    }
 }
@@ -15,9 +15,16 @@ pub const X_DEFENDS_CARRIER_AGGR: [&str; 2] = [
 
 pub const X_GOT_FLAG: [&str; 2] = [" çïô the ÒÅÄ flag!\n", " çïô the ÂÌÕÅ flag!\n"];
 
+pub const X_LOST_FLAG: [&str; 2] = [" ìïóô the ÒÅÄ flag!\n", " ìïóô the ÂÌÕÅ flag!\n"];
+
 pub const X_RETURNED_FLAG: [&str; 2] = [" òåôõòîåä the ÒÅÄ flag!\n", " òåôõòîåä the ÂÌÕÅ flag!\n"];
 
 pub const X_FRAGS_CARRIER: [&str; 2] = [
     " gets an assist for returning his flag!\n",
     " gets an assist for fragging the flag carrier!\n",
 ];
+
+pub const FLAG_RETURNED: [&str; 2] = [
+    "The ÒÅÄ flag has been returned\n",
+    "The ÂÌÕÅ flag has been returned\n",
+];
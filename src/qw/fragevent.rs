@@ -1,4 +1,4 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 
 use crate::qw::fragprint::{
     UNKNOWN_TEAMKILL_X, WILDCARD, X_DEATH, X_FRAG_Y, X_SUICIDE, X_SUICIDE_BY_WEAPON,
@@ -6,6 +6,7 @@ use crate::qw::fragprint::{
 };
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FragEvent {
     Death { player: String },
     Suicide { player: String },
@@ -16,7 +17,7 @@ pub enum FragEvent {
 }
 
 impl TryFrom<&str> for FragEvent {
-    type Error = anyhow::Error;
+    type Error = Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         if let Some(pos) = X_DEATH.iter().find_map(|&p| value.find(p)) {
@@ -51,10 +52,60 @@ impl TryFrom<&str> for FragEvent {
             }
         }
 
-        Err(e!(r#"Unable to parse message: "{}""#, value))
+        Err(Error::UnknownPrint(value.to_string()))
     }
 }
 
+impl FragEvent {
+    // match against known player names, handles names containing obituary phrases
+    pub fn from_print_and_names(value: &str, names: &[String]) -> Result<Self> {
+        let mut prefixes: Vec<&String> = names
+            .iter()
+            .filter(|n| !n.is_empty() && value.starts_with(n.as_str()))
+            .collect();
+        prefixes.sort_by_key(|n| std::cmp::Reverse(n.len()));
+
+        for x in prefixes {
+            let rest = &value[x.len()..];
+            let player = x.to_string();
+
+            if X_DEATH.contains(&rest) {
+                return Ok(FragEvent::Death { player });
+            } else if X_SUICIDE_BY_WEAPON.contains(&rest) {
+                return Ok(FragEvent::SuicideByWeapon { player });
+            } else if rest == X_SUICIDE {
+                return Ok(FragEvent::Suicide { player });
+            } else if X_TEAMKILL_UNKNOWN.contains(&rest) {
+                return Ok(FragEvent::Teamkill { killer: player });
+            } else if UNKNOWN_TEAMKILL_X.contains(&rest) {
+                return Ok(FragEvent::TeamkillByUnknown { victim: player });
+            }
+
+            for p in [X_FRAG_Y.to_vec(), Y_FRAG_X.to_vec()].concat() {
+                let Some(y) = pattern_match_suffix(rest, p).filter(|y| names.contains(y)) else {
+                    continue;
+                };
+                let (killer, victim) = match X_FRAG_Y.contains(&p) {
+                    true => (player, y),
+                    false => (y, player),
+                };
+                return Ok(FragEvent::Frag { killer, victim });
+            }
+        }
+
+        Err(Error::UnknownPrint(value.to_string()))
+    }
+}
+
+fn pattern_match_suffix(value: &str, pattern: &str) -> Option<String> {
+    let y = match pattern.split_once(WILDCARD) {
+        Some((prefix, suffix)) => value.strip_prefix(prefix)?.strip_suffix(suffix)?,
+        None => value.strip_prefix(pattern)?,
+    };
+
+    Some(y.to_string())
+}
+
 fn pattern_match(value: &str, pattern: &str) -> Option<(String, String)> {
     let (x, y) = match pattern.contains(WILDCARD) {
         true => {
@@ -222,4 +273,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_from_print_and_names() -> Result<()> {
+        let names = vec!["FOO".to_string(), "BAR".to_string(), "FOO died".to_string()];
+
+        assert_eq!(
+            FragEvent::from_print_and_names("FOO died", &names)?,
+            foo_death()?
+        );
+        assert_eq!(
+            FragEvent::from_print_and_names("FOO died was nailed by BAR", &names)?,
+            Frag {
+                killer: "BAR".to_string(),
+                victim: "FOO died".to_string(),
+            }
+        );
+        assert_eq!(
+            FragEvent::from_print_and_names("FOO rides BAR's rocket", &names)?,
+            bar_frag_foo()?
+        );
+        assert_eq!(
+            FragEvent::from_print_and_names("FOO rips BAR a new one", &names)?,
+            foo_frag_bar()?
+        );
+        assert_eq!(
+            FragEvent::from_print_and_names("FOO was crushed by his teammate", &names)?,
+            foo_tk_by_unknown()?
+        );
+        assert!(FragEvent::from_print_and_names("FOO rides BAZ's rocket", &names).is_err());
+        assert!(FragEvent::from_print_and_names("BAZ died", &names).is_err());
+
+        Ok(())
+    }
 }
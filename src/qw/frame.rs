@@ -1,9 +1,8 @@
-use std::io::Cursor;
-use std::ops::Range;
+use core::ops::Range;
 
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 
-use crate::qw::primitives::{numsize, ReadPrimitives};
+use crate::qw::primitives::{numsize, ByteReader};
 use crate::qw::prot::{Command, Target};
 
 pub const HEADER_SIZE: usize = numsize::SHORT + numsize::LONG;
@@ -14,6 +13,7 @@ pub struct Info {
     pub duration: u32,
     pub target: Target,
     pub command: Command,
+    pub is_hidden: bool,
     pub index: usize,
     pub size: usize,
     pub header_size: usize,
@@ -24,32 +24,44 @@ pub struct Info {
 
 impl Info {
     pub fn from_data_and_index(data: &[u8], index: usize) -> Result<Self> {
-        let mut cur = Cursor::new(&data[index..]);
+        let available = data.len().saturating_sub(index);
+        let truncated = |expected: usize| Error::TruncatedFrame {
+            offset: index,
+            expected,
+            available,
+        };
+        let mut reader = ByteReader::new(data.get(index..).unwrap_or_default());
 
-        let duration = cur.read_byte()? as u32;
+        let duration = reader.byte().ok_or_else(|| truncated(HEADER_SIZE))? as u32;
 
         let (target, command) = {
-            let byte = cur.read_byte()?;
+            let byte = reader.byte().ok_or_else(|| truncated(HEADER_SIZE))?;
             (Target::from(&byte), Command::from(&byte))
         };
 
-        let multi_bytes = match target {
-            Target::Multiple => numsize::LONG, // skip multi target bytes [0,0,0,0]
-            _ => 0,
+        // multi target bytes [0,0,0,0] means hidden data (mvdhidden_*)
+        let is_hidden = match target {
+            Target::Multiple => reader.u32().ok_or_else(|| truncated(MULTI_HEADER_SIZE))? == 0,
+            _ => false,
         };
-        cur.set_position(cur.position() + multi_bytes as u64);
 
         let body_size = match command {
-            Command::Read => cur.read_u32()? as usize,
+            Command::Read => {
+                let header_size = reader.position() + numsize::LONG;
+                reader.u32().ok_or_else(|| truncated(header_size))? as usize
+            }
             Command::Set => 2 * numsize::LONG, // reads 2 longs (8 bytes)
             _ => 0,                            // should not happen
         };
 
-        let header_size = cur.position() as usize;
-        let size = header_size + body_size;
+        let header_size = reader.position();
+        // body size is read from the file and may not fit in a usize on 32-bit targets
+        let size = header_size
+            .checked_add(body_size)
+            .ok_or_else(|| truncated(usize::MAX))?;
 
-        if data.len() < size {
-            return Err(e!("Frame is smaller than expected size"));
+        if available < size {
+            return Err(truncated(size));
         }
 
         Ok(Self {
@@ -57,6 +69,7 @@ impl Info {
             duration,
             target,
             command,
+            is_hidden,
             size,
             header_size,
             header_range: index..index + header_size,
@@ -64,16 +77,18 @@ impl Info {
             body_range: index + header_size..index + size,
         })
     }
-}
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct SliceInfo {
-    pub size: usize,
-    pub range_abs: Range<usize>,
-    // pub range_rel: Range<usize>,
+    // single and stats target frames have the player number in the upper 5 bits of the target byte
+    #[cfg(feature = "std")]
+    pub fn player_number(&self, data: &[u8]) -> Option<u8> {
+        match self.target {
+            Target::Single | Target::Stats => data.get(self.index + 1).map(|b| b >> 3),
+            _ => None,
+        }
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use std::fs::read;
 
@@ -82,17 +97,52 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_truncated_frame() {
+        let data: [u8; 10] = [9, 9, 0, 6, 4, 0, 0, 0, 1, 2];
+
+        assert!(matches!(
+            Info::from_data_and_index(&data, 2),
+            Err(Error::TruncatedFrame {
+                offset: 2,
+                expected: 10,
+                available: 8,
+            })
+        ));
+        assert!(matches!(
+            Info::from_data_and_index(&data, 9),
+            Err(Error::TruncatedFrame {
+                offset: 9,
+                expected: 6,
+                available: 1,
+            })
+        ));
+        assert!(matches!(
+            Info::from_data_and_index(&data, 20),
+            Err(Error::TruncatedFrame { available: 0, .. })
+        ));
+
+        // body size larger than the demo
+        let data: [u8; 6] = [0, 4, 0xff, 0xff, 0xff, 0xff];
+        assert!(matches!(
+            Info::from_data_and_index(&data, 0),
+            Err(Error::TruncatedFrame {
+                offset: 0,
+                available: 6,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_from_data_and_index() -> Result<()> {
         let data = read("tests/files/4on4_oeks_vs_tsq[dm2]20240426-1716.mvd")?;
 
         {
-            assert_eq!(
-                Info::from_data_and_index(&data[0..10], 0)
-                    .unwrap_err()
-                    .to_string(),
-                "Frame is smaller than expected size".to_string()
-            );
+            assert!(matches!(
+                Info::from_data_and_index(&data[0..10], 0),
+                Err(Error::TruncatedFrame { offset: 0, .. })
+            ));
         }
 
         {
@@ -103,6 +153,7 @@ mod tests {
                     duration: 0,
                     target: Target::All,
                     command: Command::Read,
+                    is_hidden: false,
                     size: 743,
                     header_size: 6,
                     header_range: 456..462,
@@ -0,0 +1,136 @@
+use crate::qw::message::playerinfo::{read_playerinfo, Playerinfo};
+use crate::qw::message::print_ref::read_print;
+use crate::qw::message::serverinfo::{read_serverinfo, ServerinfoRef};
+use crate::qw::message::setinfo::{read_setinfo, SetinfoRef};
+use crate::qw::message::update_frags::{read_update_frags, UpdateFrags};
+use crate::qw::message::update_ping::{read_update_ping, UpdatePing};
+use crate::qw::message::update_pl::{read_update_pl, UpdatePl};
+use crate::qw::message::update_stat::{read_update_stat, read_update_stat_long, UpdateStat};
+use crate::qw::message::update_userinfo::{read_update_userinfo, UpdateUserinfoRef};
+use crate::qw::message::PrintRef;
+use crate::qw::primitives::ByteReader;
+use crate::qw::prot::MessageType;
+
+// borrowed from the frame body, see Message for owned messages
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageRef<'a> {
+    Print(PrintRef<'a>),
+    CenterPrint(&'a [u8]),
+    Stufftext(&'a [u8]),
+    UpdateFrags(UpdateFrags),
+    UpdatePing(UpdatePing),
+    UpdatePl(UpdatePl),
+    UpdateStat(UpdateStat),
+    UpdateUserinfo(UpdateUserinfoRef<'a>),
+    Setinfo(SetinfoRef<'a>),
+    Serverinfo(ServerinfoRef<'a>),
+    SetPause(bool),
+    Playerinfo(Playerinfo),
+    Other(MessageType), // skipped, content not read
+}
+
+pub struct MessageRefs<'a> {
+    reader: ByteReader<'a>,
+}
+
+impl MessageRefs<'_> {
+    // offset in body of the next message
+    pub fn position(&self) -> usize {
+        self.reader.position()
+    }
+}
+
+impl<'a> Iterator for MessageRefs<'a> {
+    type Item = MessageRef<'a>;
+
+    // ends at end of body or the first message that can not be read
+    fn next(&mut self) -> Option<Self::Item> {
+        let reader = &mut self.reader;
+        let message = match MessageType::from(&reader.byte()?) {
+            MessageType::Print => MessageRef::Print(read_print(reader)?),
+            MessageType::CenterPrint => MessageRef::CenterPrint(reader.bstr()?),
+            MessageType::Stufftext => MessageRef::Stufftext(reader.bstr()?),
+            MessageType::UpdateFrags => MessageRef::UpdateFrags(read_update_frags(reader)?),
+            MessageType::UpdatePing => MessageRef::UpdatePing(read_update_ping(reader)?),
+            MessageType::UpdatePl => MessageRef::UpdatePl(read_update_pl(reader)?),
+            MessageType::UpdateStat => MessageRef::UpdateStat(read_update_stat(reader)?),
+            MessageType::UpdateStatLong => MessageRef::UpdateStat(read_update_stat_long(reader)?),
+            MessageType::UpdateUserinfo => {
+                MessageRef::UpdateUserinfo(read_update_userinfo(reader)?)
+            }
+            MessageType::Setinfo => MessageRef::Setinfo(read_setinfo(reader)?),
+            MessageType::Serverinfo => MessageRef::Serverinfo(read_serverinfo(reader)?),
+            MessageType::SetPause => MessageRef::SetPause(reader.byte()? != 0),
+            MessageType::Playerinfo => MessageRef::Playerinfo(read_playerinfo(reader)?),
+            message_type => {
+                reader.skip(message_type.fixed_size()?)?;
+                MessageRef::Other(message_type)
+            }
+        };
+        Some(message)
+    }
+}
+
+pub fn message_refs(body: &[u8]) -> MessageRefs<'_> {
+    MessageRefs {
+        reader: ByteReader::new(body),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::qw::message::{messages, Message};
+    use crate::qw::prot::PrintId;
+
+    use super::*;
+
+    #[test]
+    fn test_message_refs() {
+        let body: &[u8] = b"\x08\x01FOO rides BAR's rocket\n\0\x09//ktx drop 1\n\0\x33\x02name\0XantoM\0\x0E\x02\x05\x00\x28\x02\x07\x00\x00\x00\\name\\XantoM\0\x24\x02\x19\x00\x22\x2F\x0E";
+
+        assert_eq!(
+            message_refs(body).collect::<Vec<_>>(),
+            vec![
+                MessageRef::Print(PrintRef {
+                    id: PrintId::Medium,
+                    content: b"FOO rides BAR's rocket\n",
+                }),
+                MessageRef::Stufftext(b"//ktx drop 1\n"),
+                MessageRef::Setinfo(SetinfoRef {
+                    player_number: 2,
+                    key: b"name",
+                    value: b"XantoM",
+                }),
+                MessageRef::UpdateFrags(UpdateFrags {
+                    player_number: 2,
+                    frags: 5,
+                }),
+                MessageRef::UpdateUserinfo(UpdateUserinfoRef {
+                    player_number: 2,
+                    user_id: 7,
+                    userinfo: b"\\name\\XantoM",
+                }),
+                MessageRef::UpdatePing(UpdatePing {
+                    player_number: 2,
+                    ping: 25,
+                }),
+                MessageRef::Other(MessageType::Smallkick),
+            ]
+        );
+        assert_eq!(message_refs(&[]).count(), 0);
+        assert_eq!(message_refs(b"\x08\x01no terminator").count(), 0);
+
+        // position after each message
+        let mut refs = message_refs(body);
+        refs.next();
+        assert_eq!(refs.position(), 26);
+
+        // same messages as the owned reader
+        assert_eq!(
+            message_refs(body).map(Message::from).collect::<Vec<_>>(),
+            messages(body)
+        );
+    }
+}
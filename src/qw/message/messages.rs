@@ -0,0 +1,92 @@
+use crate::qw::message::playerinfo::Playerinfo;
+use crate::qw::message::serverinfo::Serverinfo;
+use crate::qw::message::setinfo::Setinfo;
+use crate::qw::message::update_frags::UpdateFrags;
+use crate::qw::message::update_ping::UpdatePing;
+use crate::qw::message::update_pl::UpdatePl;
+use crate::qw::message::update_stat::UpdateStat;
+use crate::qw::message::update_userinfo::UpdateUserinfo;
+use crate::qw::message::{message_refs, MessageRef, Print};
+use crate::qw::prot::MessageType;
+
+#[derive(Debug, PartialEq)]
+pub enum Message {
+    Print(Print),
+    CenterPrint(Vec<u8>),
+    Stufftext(Vec<u8>),
+    UpdateFrags(UpdateFrags),
+    UpdatePing(UpdatePing),
+    UpdatePl(UpdatePl),
+    UpdateStat(UpdateStat),
+    UpdateUserinfo(UpdateUserinfo),
+    Setinfo(Setinfo),
+    Serverinfo(Serverinfo),
+    SetPause(bool),
+    Playerinfo(Playerinfo),
+    Other(MessageType), // skipped, content not read
+}
+
+impl From<MessageRef<'_>> for Message {
+    fn from(value: MessageRef<'_>) -> Self {
+        match value {
+            MessageRef::Print(p) => Message::Print(p.to_print()),
+            MessageRef::CenterPrint(c) => Message::CenterPrint(c.to_vec()),
+            MessageRef::Stufftext(c) => Message::Stufftext(c.to_vec()),
+            MessageRef::UpdateFrags(u) => Message::UpdateFrags(u),
+            MessageRef::UpdatePing(u) => Message::UpdatePing(u),
+            MessageRef::UpdatePl(u) => Message::UpdatePl(u),
+            MessageRef::UpdateStat(u) => Message::UpdateStat(u),
+            MessageRef::UpdateUserinfo(u) => Message::UpdateUserinfo(u.into()),
+            MessageRef::Setinfo(s) => Message::Setinfo(s.into()),
+            MessageRef::Serverinfo(s) => Message::Serverinfo(s.into()),
+            MessageRef::SetPause(p) => Message::SetPause(p),
+            MessageRef::Playerinfo(p) => Message::Playerinfo(p),
+            MessageRef::Other(t) => Message::Other(t),
+        }
+    }
+}
+
+// reads messages until end of body or the first message that can not be read
+pub fn messages(body: &[u8]) -> Vec<Message> {
+    message_refs(body).map(Message::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::qw::prot::PrintId;
+
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let body: &[u8] = b"\x08\x01FOO rides BAR's rocket\n\0\x09//ktx drop 1\n\0\x0E\x02\x05\x00\x24\x02\x19\x00\x35\x02\x01\x18\x01\x22\x2F\x0E";
+
+        assert_eq!(
+            messages(body),
+            vec![
+                Message::Print(Print {
+                    id: PrintId::Medium,
+                    content: b"FOO rides BAR's rocket\n".to_vec(),
+                }),
+                Message::Stufftext(b"//ktx drop 1\n".to_vec()),
+                Message::UpdateFrags(UpdateFrags {
+                    player_number: 2,
+                    frags: 5,
+                }),
+                Message::UpdatePing(UpdatePing {
+                    player_number: 2,
+                    ping: 25,
+                }),
+                Message::UpdatePl(UpdatePl {
+                    player_number: 2,
+                    packet_loss: 1,
+                }),
+                Message::SetPause(true),
+                Message::Other(MessageType::Smallkick),
+            ]
+        );
+        assert_eq!(messages(&[]), vec![]);
+    }
+}
@@ -1,6 +1,21 @@
+pub use message_ref::{message_refs, MessageRef};
+#[cfg(feature = "std")]
+pub use messages::{messages, Message};
+#[cfg(feature = "std")]
 pub use print::Print;
+pub use print_ref::PrintRef;
 
-pub mod message_type;
+pub mod message_ref;
+#[cfg(feature = "std")]
+pub mod messages;
+pub mod playerinfo;
+#[cfg(feature = "std")]
 pub mod print;
+pub mod print_ref;
+pub mod serverinfo;
+pub mod setinfo;
 pub mod update_frags;
 pub mod update_ping;
+pub mod update_pl;
+pub mod update_stat;
+pub mod update_userinfo;
@@ -0,0 +1,89 @@
+use crate::qw::primitives::ByteReader;
+
+// mvd playerinfo flags, fields not included are unchanged since the previous frame
+pub const DF_ORIGIN: u16 = 1; // 3 bits, one per axis
+pub const DF_ANGLES: u16 = 1 << 3; // 3 bits, one per axis
+pub const DF_EFFECTS: u16 = 1 << 6;
+pub const DF_SKINNUM: u16 = 1 << 7;
+pub const DF_WEAPONFRAME: u16 = 1 << 10;
+pub const DF_MODEL: u16 = 1 << 11;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Playerinfo {
+    pub player_number: u8,
+    pub flags: u16,
+    pub frame: u8,
+    pub origin: [Option<f32>; 3],
+    pub angles: [Option<f32>; 3],
+    pub model: Option<u8>,
+    pub skinnum: Option<u8>,
+    pub effects: Option<u8>,
+    pub weaponframe: Option<u8>,
+}
+
+pub fn read_playerinfo(reader: &mut ByteReader) -> Option<Playerinfo> {
+    let player_number = reader.byte()?;
+    let flags = reader.u16()?;
+    let frame = reader.byte()?;
+    let mut info = Playerinfo {
+        player_number,
+        flags,
+        frame,
+        ..Default::default()
+    };
+
+    for axis in 0..3 {
+        if flags & (DF_ORIGIN << axis) != 0 {
+            info.origin[axis] = Some(reader.u16()? as i16 as f32 / 8.0);
+        }
+    }
+
+    for axis in 0..3 {
+        if flags & (DF_ANGLES << axis) != 0 {
+            info.angles[axis] = Some(reader.u16()? as f32 * 360.0 / 65536.0);
+        }
+    }
+
+    let mut optional_byte = |flag: u16| -> Option<Option<u8>> {
+        match flags & flag {
+            0 => Some(None),
+            _ => reader.byte().map(Some),
+        }
+    };
+    info.model = optional_byte(DF_MODEL)?;
+    info.skinnum = optional_byte(DF_SKINNUM)?;
+    info.effects = optional_byte(DF_EFFECTS)?;
+    info.weaponframe = optional_byte(DF_WEAPONFRAME)?;
+    Some(info)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_read_playerinfo() {
+        let flags = (DF_ORIGIN | DF_ORIGIN << 2 | DF_ANGLES << 1 | DF_EFFECTS).to_le_bytes();
+        let bytes = [
+            &[3],
+            flags.as_slice(),
+            &[7, 0x10, 0, 0xF0, 0xFF, 0, 0x40, 8],
+        ]
+        .concat();
+
+        assert_eq!(
+            read_playerinfo(&mut ByteReader::new(&bytes)).unwrap(),
+            Playerinfo {
+                player_number: 3,
+                flags: u16::from_le_bytes(flags),
+                frame: 7,
+                origin: [Some(2.0), None, Some(-2.0)],
+                angles: [None, Some(90.0), None],
+                effects: Some(8),
+                ..Default::default()
+            }
+        );
+    }
+}
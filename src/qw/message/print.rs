@@ -1,14 +1,13 @@
 use std::fmt::Debug;
-use std::io::{Cursor, Read};
 
-use anyhow::Result;
-
-use crate::qw::primitives::ReadPrimitives;
+use crate::qw::message::PrintRef;
 use crate::qw::prot::PrintId;
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Print {
     pub id: PrintId,
+    #[cfg_attr(feature = "serde", serde(with = "crate::bytesextra::unicode_string"))]
     pub content: Vec<u8>,
 }
 
@@ -27,42 +26,40 @@ impl Debug for Print {
     }
 }
 
-impl TryFrom<&[u8]> for Print {
-    type Error = std::io::Error;
-
-    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        Cursor::new(value).read_print()
+impl PrintRef<'_> {
+    pub fn to_print(&self) -> Print {
+        Print {
+            id: self.id,
+            content: self.content.to_vec(),
+        }
     }
 }
 
-pub trait ReadPrint: ReadPrimitives {
-    fn read_print(&mut self) -> std::io::Result<Print> {
-        Ok(Print {
-            id: PrintId::from(&self.read_byte()?),
-            content: self.read_bstring()?,
-        })
+impl Debug for PrintRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrintRef")
+            .field("id", &self.id)
+            .field("content", &quake_text::bytestr::to_ascii(self.content))
+            .finish()
     }
 }
 
-impl<R: Read + ?Sized> ReadPrint for R {}
-
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
     use pretty_assertions::assert_eq;
 
+    use crate::qw::message::print_ref::read_print;
     use crate::qw::message::Print;
+    use crate::qw::primitives::ByteReader;
     use crate::qw::prot::PrintId;
 
     #[test]
-    fn test_try_from() -> Result<()> {
+    fn test_read_print() -> Result<()> {
         // missing null terminator
         {
             let data: &[u8] = &[1, 2, 3, 4, 5, 10];
-            assert_eq!(
-                Print::try_from(data).unwrap_err().to_string(),
-                "failed to read string".to_string()
-            );
+            assert_eq!(read_print(&mut ByteReader::new(data)), None);
         }
 
         // valid
@@ -72,7 +69,8 @@ mod tests {
                 id: PrintId::Medium,
                 content: vec![2, 3, 4, 10],
             };
-            assert_eq!(Print::try_from(data)?, print);
+            let print_ref = read_print(&mut ByteReader::new(data));
+            assert_eq!(print_ref.map(|p| p.to_print()), Some(print.clone()));
             assert_eq!(print.byte_size(), 6);
         }
 
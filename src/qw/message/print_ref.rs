@@ -0,0 +1,17 @@
+use crate::qw::primitives::ByteReader;
+use crate::qw::prot::PrintId;
+
+// borrowed from demo data, see Print for an owned print
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "std"), derive(Debug))]
+pub struct PrintRef<'a> {
+    pub id: PrintId,
+    pub content: &'a [u8],
+}
+
+pub fn read_print<'a>(reader: &mut ByteReader<'a>) -> Option<PrintRef<'a>> {
+    Some(PrintRef {
+        id: PrintId::from(&reader.byte()?),
+        content: reader.bstr()?,
+    })
+}
@@ -0,0 +1,52 @@
+use crate::qw::primitives::ByteReader;
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+pub struct Serverinfo {
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+// borrowed from the frame body, see Serverinfo for an owned message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServerinfoRef<'a> {
+    pub key: &'a [u8],
+    pub value: &'a [u8],
+}
+
+#[cfg(feature = "std")]
+impl From<ServerinfoRef<'_>> for Serverinfo {
+    fn from(value: ServerinfoRef<'_>) -> Self {
+        Self {
+            key: value.key.to_vec(),
+            value: value.value.to_vec(),
+        }
+    }
+}
+
+pub fn read_serverinfo<'a>(reader: &mut ByteReader<'a>) -> Option<ServerinfoRef<'a>> {
+    Some(ServerinfoRef {
+        key: reader.bstr()?,
+        value: reader.bstr()?,
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_serverinfo() {
+        let bytes: &[u8] = b"timelimit\x0020\0";
+
+        assert_eq!(
+            Serverinfo::from(read_serverinfo(&mut ByteReader::new(bytes)).unwrap()),
+            Serverinfo {
+                key: b"timelimit".to_vec(),
+                value: b"20".to_vec(),
+            }
+        );
+    }
+}
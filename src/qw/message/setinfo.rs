@@ -0,0 +1,57 @@
+use crate::qw::primitives::ByteReader;
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+pub struct Setinfo {
+    pub player_number: u8,
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+// borrowed from the frame body, see Setinfo for an owned message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SetinfoRef<'a> {
+    pub player_number: u8,
+    pub key: &'a [u8],
+    pub value: &'a [u8],
+}
+
+#[cfg(feature = "std")]
+impl From<SetinfoRef<'_>> for Setinfo {
+    fn from(value: SetinfoRef<'_>) -> Self {
+        Self {
+            player_number: value.player_number,
+            key: value.key.to_vec(),
+            value: value.value.to_vec(),
+        }
+    }
+}
+
+pub fn read_setinfo<'a>(reader: &mut ByteReader<'a>) -> Option<SetinfoRef<'a>> {
+    Some(SetinfoRef {
+        player_number: reader.byte()?,
+        key: reader.bstr()?,
+        value: reader.bstr()?,
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_setinfo() {
+        let bytes: &[u8] = b"\x02name\0XantoM\0";
+
+        assert_eq!(
+            Setinfo::from(read_setinfo(&mut ByteReader::new(bytes)).unwrap()),
+            Setinfo {
+                player_number: 2,
+                key: b"name".to_vec(),
+                value: b"XantoM".to_vec(),
+            }
+        );
+    }
+}
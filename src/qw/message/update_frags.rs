@@ -1,33 +1,19 @@
-use std::io::{Cursor, Read};
+use crate::qw::primitives::ByteReader;
 
-use crate::qw::primitives::ReadPrimitives;
-
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct UpdateFrags {
     pub player_number: u8,
-    pub frags: u16,
+    pub frags: i16,
 }
 
-impl TryFrom<&[u8]> for UpdateFrags {
-    type Error = std::io::Error;
-
-    fn try_from(value: &[u8]) -> anyhow::Result<Self, Self::Error> {
-        Cursor::new(value).read_update_frags()
-    }
+pub fn read_update_frags(reader: &mut ByteReader) -> Option<UpdateFrags> {
+    Some(UpdateFrags {
+        player_number: reader.byte()?,
+        frags: reader.u16()? as i16,
+    })
 }
 
-pub trait ReadUpdateFrags: ReadPrimitives {
-    fn read_update_frags(&mut self) -> std::io::Result<UpdateFrags> {
-        Ok(UpdateFrags {
-            player_number: self.read_byte()?,
-            frags: self.read_u16()?,
-        })
-    }
-}
-
-impl<R: Read + ?Sized> ReadUpdateFrags for R {}
-
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use pretty_assertions::assert_eq;
 
@@ -38,11 +24,21 @@ mod tests {
         let bytes: &[u8] = &[9, 23, 1];
 
         assert_eq!(
-            UpdateFrags::try_from(bytes).unwrap(),
+            read_update_frags(&mut ByteReader::new(bytes)).unwrap(),
             UpdateFrags {
                 player_number: 9,
                 frags: 279,
             }
         );
+
+        let bytes: &[u8] = &[2, 254, 255];
+
+        assert_eq!(
+            read_update_frags(&mut ByteReader::new(bytes)).unwrap(),
+            UpdateFrags {
+                player_number: 2,
+                frags: -2,
+            }
+        );
     }
 }
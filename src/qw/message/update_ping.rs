@@ -1,35 +1,19 @@
-use std::io::{Cursor, Read};
+use crate::qw::primitives::ByteReader;
 
-use crate::qw::primitives::ReadPrimitives;
-
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct UpdatePing {
     pub player_number: u8,
     pub ping: u16,
 }
 
-impl TryFrom<&[u8]> for UpdatePing {
-    type Error = std::io::Error;
-
-    fn try_from(value: &[u8]) -> anyhow::Result<Self, Self::Error> {
-        Cursor::new(value).read_update_ping()
-    }
+pub fn read_update_ping(reader: &mut ByteReader) -> Option<UpdatePing> {
+    Some(UpdatePing {
+        player_number: reader.byte()?,
+        ping: reader.u16()?,
+    })
 }
 
-pub trait ReadUpdatePing: ReadPrimitives {
-    fn read_update_ping(&mut self) -> std::io::Result<UpdatePing> {
-        let ping = UpdatePing {
-            player_number: self.read_byte()?,
-            ping: self.read_u16()?,
-        };
-        self.read_byte()?;
-        Ok(ping)
-    }
-}
-
-impl<R: Read + ?Sized> ReadUpdatePing for R {}
-
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use pretty_assertions::assert_eq;
 
@@ -37,10 +21,10 @@ mod tests {
 
     #[test]
     fn test_update_ping() {
-        let bytes: &[u8] = &[4, 1, 2, 0];
+        let bytes: &[u8] = &[4, 1, 2];
 
         assert_eq!(
-            UpdatePing::try_from(bytes).unwrap(),
+            read_update_ping(&mut ByteReader::new(bytes)).unwrap(),
             UpdatePing {
                 player_number: 4,
                 ping: 513,
@@ -0,0 +1,34 @@
+use crate::qw::primitives::ByteReader;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdatePl {
+    pub player_number: u8,
+    pub packet_loss: u8,
+}
+
+pub fn read_update_pl(reader: &mut ByteReader) -> Option<UpdatePl> {
+    Some(UpdatePl {
+        player_number: reader.byte()?,
+        packet_loss: reader.byte()?,
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_update_pl() {
+        let bytes: &[u8] = &[4, 12];
+
+        assert_eq!(
+            read_update_pl(&mut ByteReader::new(bytes)).unwrap(),
+            UpdatePl {
+                player_number: 4,
+                packet_loss: 12,
+            }
+        );
+    }
+}
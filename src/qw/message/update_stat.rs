@@ -0,0 +1,47 @@
+use crate::qw::primitives::ByteReader;
+
+// svc_updatestat and svc_updatestatlong, sent to the player the stat belongs to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateStat {
+    pub stat: u8,
+    pub value: i32,
+}
+
+pub fn read_update_stat(reader: &mut ByteReader) -> Option<UpdateStat> {
+    Some(UpdateStat {
+        stat: reader.byte()?,
+        value: reader.byte()? as i32,
+    })
+}
+
+pub fn read_update_stat_long(reader: &mut ByteReader) -> Option<UpdateStat> {
+    Some(UpdateStat {
+        stat: reader.byte()?,
+        value: reader.u32()? as i32,
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_update_stat() {
+        assert_eq!(
+            read_update_stat(&mut ByteReader::new(&[1, 100])),
+            Some(UpdateStat {
+                stat: 1,
+                value: 100,
+            })
+        );
+        assert_eq!(
+            read_update_stat_long(&mut ByteReader::new(&[15, 1, 0, 0, 0x10])),
+            Some(UpdateStat {
+                stat: 15,
+                value: 0x1000_0001,
+            })
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use crate::qw::primitives::ByteReader;
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+pub struct UpdateUserinfo {
+    pub player_number: u8,
+    pub user_id: u32,
+    pub userinfo: Vec<u8>,
+}
+
+// borrowed from the frame body, see UpdateUserinfo for an owned message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateUserinfoRef<'a> {
+    pub player_number: u8,
+    pub user_id: u32,
+    pub userinfo: &'a [u8],
+}
+
+#[cfg(feature = "std")]
+impl From<UpdateUserinfoRef<'_>> for UpdateUserinfo {
+    fn from(value: UpdateUserinfoRef<'_>) -> Self {
+        Self {
+            player_number: value.player_number,
+            user_id: value.user_id,
+            userinfo: value.userinfo.to_vec(),
+        }
+    }
+}
+
+pub fn read_update_userinfo<'a>(reader: &mut ByteReader<'a>) -> Option<UpdateUserinfoRef<'a>> {
+    Some(UpdateUserinfoRef {
+        player_number: reader.byte()?,
+        user_id: reader.u32()?,
+        userinfo: reader.bstr()?,
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_update_userinfo() {
+        let bytes: &[u8] = b"\x03\x05\x00\x00\x00\\name\\XantoM\0";
+
+        assert_eq!(
+            UpdateUserinfo::from(read_update_userinfo(&mut ByteReader::new(bytes)).unwrap()),
+            UpdateUserinfo {
+                player_number: 3,
+                user_id: 5,
+                userinfo: br#"\name\XantoM"#.to_vec(),
+            }
+        );
+        assert_eq!(
+            read_update_userinfo(&mut ByteReader::new(&bytes[..12])),
+            None
+        );
+    }
+}
@@ -1,9 +1,18 @@
+#[cfg(feature = "std")]
 pub mod block;
+#[cfg(feature = "std")]
 pub mod flagevent;
+#[cfg(feature = "std")]
 pub mod flagprint;
+#[cfg(feature = "std")]
 pub mod fragevent;
+#[cfg(feature = "std")]
 pub mod fragprint;
 pub mod frame;
 pub mod message;
 pub mod primitives;
 pub mod prot;
+#[cfg(feature = "std")]
+pub mod raceprint;
+#[cfg(feature = "std")]
+pub mod runeprint;
@@ -1,86 +1,91 @@
-use std::io::{Error, ErrorKind, Read, Result};
-
 pub mod numsize {
     pub const SHORT: usize = 2;
     pub const LONG: usize = 4;
 }
 
-pub fn err_other(msg: &str) -> Error {
-    Error::new(ErrorKind::Other, msg)
+// reads from a byte slice without copying, available without std
+#[derive(Clone, Debug)]
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
 }
 
-pub trait ReadPrimitives: Read {
-    fn read_byte(&mut self) -> Result<u8> {
-        let mut buf = [0; 1];
-        self.read_exact(&mut buf)
-            .map_err(|_| err_other("failed to read byte"))?;
-        Ok(buf[0])
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
     }
 
-    fn read_u16(&mut self) -> Result<u16> {
-        let mut buf = [0; 2];
-        self.read_exact(&mut buf)
-            .map_err(|_| err_other("failed to read short"))?;
-        Ok(u16::from_le_bytes([buf[0], buf[1]]))
+    pub fn position(&self) -> usize {
+        self.position
     }
 
-    fn read_u32(&mut self) -> Result<u32> {
-        let mut buf = [0; 4];
-        self.read_exact(&mut buf)
-            .map_err(|_| err_other("failed to read long"))?;
-        Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+    pub fn bytes(&mut self, size: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(size)?;
+        let bytes = self.data.get(self.position..end)?;
+        self.position = end;
+        Some(bytes)
     }
 
-    fn read_bstring(&mut self) -> Result<Vec<u8>> {
-        let mut result = Vec::new();
-        let mut buf = [0; 1];
-        loop {
-            self.read_exact(&mut buf)
-                .map_err(|_| err_other("failed to read string"))?;
-            if buf == [0] {
-                break;
-            }
-            result.push(buf[0]);
-        }
-        Ok(result)
+    pub fn skip(&mut self, size: usize) -> Option<()> {
+        self.bytes(size).map(|_| ())
     }
-}
 
-impl<R: Read + ?Sized> ReadPrimitives for R {}
+    pub fn byte(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::io::Cursor;
+    pub fn u16(&mut self) -> Option<u16> {
+        self.bytes(numsize::SHORT)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.bytes(numsize::LONG)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
 
+    // null terminated string, without the terminator
+    pub fn bstr(&mut self) -> Option<&'a [u8]> {
+        let rest = self.data.get(self.position..)?;
+        let len = rest.iter().position(|b| *b == 0)?;
+        self.position += len + 1;
+        Some(&rest[..len])
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
 
     #[test]
-    fn test_read_byte() {
-        let data: &[u8] = &[8];
-        let mut buf = Cursor::new(data);
-        assert_eq!(buf.read_byte().unwrap(), 8);
-    }
+    fn test_byte_reader() {
+        let data: &[u8] = &[8, 1, 2, 3, 1, 70, 79, 79, 0, 9];
+        let mut reader = ByteReader::new(data);
 
-    #[test]
-    fn test_read_u16() {
-        let data: &[u8] = &[1, 2];
-        let mut buf = Cursor::new(data);
-        assert_eq!(buf.read_u16().unwrap(), 513);
+        assert_eq!(reader.byte(), Some(8));
+        assert_eq!(reader.u32(), Some(16974337));
+        assert_eq!(reader.bstr(), Some(b"FOO".as_slice()));
+        assert_eq!(reader.position(), 9);
+        assert_eq!(reader.bstr(), None);
+        assert_eq!(reader.u32(), None);
+        assert_eq!(reader.position(), 9);
+        assert_eq!(reader.skip(1), Some(()));
+        assert_eq!(reader.byte(), None);
     }
 
     #[test]
-    fn test_read_u32() {
-        let data: &[u8] = &[1, 2, 3, 1];
-        let mut buf = Cursor::new(data);
-        assert_eq!(buf.read_u32().unwrap(), 16974337);
+    fn test_u16() {
+        let mut reader = ByteReader::new(&[1, 2, 3]);
+        assert_eq!(reader.u16(), Some(513));
+        assert_eq!(reader.u16(), None);
+        assert_eq!(reader.position(), 2);
     }
 
     #[test]
-    fn test_read_bstring() {
-        let data: &[u8] = &[1, 2, 3, 10, 0];
-        let mut buf = Cursor::new(data);
-        assert_eq!(buf.read_bstring().unwrap(), vec![1, 2, 3, 10]);
+    fn test_bstr_with_newline() {
+        let mut reader = ByteReader::new(&[1, 2, 3, 10, 0]);
+        assert_eq!(reader.bstr(), Some([1, 2, 3, 10].as_slice()));
     }
 }
@@ -40,7 +40,7 @@ impl From<&u8> for Command {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MessageType {
     Bad = 0,
     Nop = 1,
@@ -174,6 +174,28 @@ impl From<&u8> for MessageType {
     }
 }
 
+impl MessageType {
+    // body size of messages that do not vary in length (excluding type byte)
+    pub fn fixed_size(&self) -> Option<usize> {
+        match self {
+            MessageType::Nop
+            | MessageType::Disconnect
+            | MessageType::Killedmonster
+            | MessageType::FoundSecret
+            | MessageType::Sellscreen
+            | MessageType::Smallkick
+            | MessageType::Bigkick => Some(0),
+            MessageType::SetPause | MessageType::Cdtrack | MessageType::ChokeCount => Some(1),
+            MessageType::UpdateStat | MessageType::Muzzleflash | MessageType::UpdatePl => Some(2),
+            MessageType::UpdateFrags | MessageType::UpdatePing => Some(3),
+            MessageType::Maxspeed | MessageType::Entgravity => Some(4),
+            MessageType::UpdateEntertime | MessageType::UpdateStatLong => Some(5),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 #[derive(Clone, Debug, PartialEq)]
 pub enum HiddenMessage {
     AntilagPosition = 0x0000, // mvdhidden_antilag_position_header_t mvdhidden_antilag_position_t*
@@ -191,6 +213,7 @@ pub enum HiddenMessage {
     Unknown = 0xDEAD,
 }
 
+#[cfg(feature = "std")]
 impl From<&u16> for HiddenMessage {
     fn from(value: &u16) -> Self {
         match value {
@@ -211,7 +234,8 @@ impl From<&u16> for HiddenMessage {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrintId {
     Low = 0,
     Medium = 1,
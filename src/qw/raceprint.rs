@@ -0,0 +1,14 @@
+// KTX race mode prints (color insensitive, as ascii)
+// not yet verified against KTX src/race.c and there is no race demo in tests/files,
+// so these are best guesses until both are available
+pub const X_FINISHED_IN: &str = " finished in ";
+pub const X_REACHED_CHECKPOINT: &str = " reached checkpoint ";
+pub const X_DID_NOT_FINISH: [&str; 2] = [" did not finish", " failed to finish"];
+
+pub const NEW_RECORD: [&str; 2] = ["new record!", "new race record!"];
+pub const PERSONAL_BEST: [&str; 2] = ["personal best!", "new personal best!"];
+
+// "<key>: <value>"
+pub const SETTING_ROUTE: &str = "route: ";
+pub const SETTING_WEAPON: &str = "weapon: ";
+pub const SETTING_FALSESTART: &str = "falsestart: ";
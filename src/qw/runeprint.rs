@@ -0,0 +1,8 @@
+// KTX rune pickups, sent to the player picking up the rune (color insensitive, as ascii)
+// same order as the sigil items: resistance, strength, haste, regeneration
+pub const YOU_GOT_RUNE: [&str; 4] = [
+    "You got the resistance rune",
+    "You got the strength rune",
+    "You got the haste rune",
+    "You got the regeneration rune",
+];
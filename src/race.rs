@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use quake_text::bytestr::{to_ascii, to_unicode};
+
+use crate::prints::{timed_prints, TimedPrint};
+use crate::qw::prot::PrintId;
+use crate::qw::raceprint;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaceSettings {
+    pub route: Option<String>,
+    pub weapon: Option<String>,
+    pub falsestart: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaceRun {
+    pub index: usize,
+    pub time: Duration, // game time when the run ended
+    pub player: String,
+    pub run_time: Option<Duration>, // none if the run did not finish
+    pub checkpoints: Vec<Duration>,
+    pub settings: RaceSettings,
+    pub is_record: bool,
+    pub is_personal_best: bool,
+}
+
+impl RaceRun {
+    pub fn is_finished(&self) -> bool {
+        self.run_time.is_some()
+    }
+}
+
+pub fn race_runs(data: &[u8]) -> Vec<RaceRun> {
+    race_runs_from_prints(&timed_prints(data))
+}
+
+pub fn race_runs_from_prints(prints: &[TimedPrint]) -> Vec<RaceRun> {
+    let mut runs: Vec<RaceRun> = vec![];
+    let mut settings = RaceSettings::default();
+    let mut checkpoints: Vec<(String, Duration)> = vec![];
+
+    // race prints are broadcast by the server, chat can not fake a run
+    for print in prints.iter().filter(|p| p.print.id == PrintId::High) {
+        let content = print.print.content.as_slice();
+
+        for raw in content.split(|b| *b == b'\n') {
+            let text = to_ascii(raw);
+            let text = text.trim();
+
+            if text.is_empty() {
+                continue;
+            }
+
+            let name_of = |name: &str| to_unicode(&raw[..name.len()]);
+
+            if let Some(value) = text.strip_prefix(raceprint::SETTING_ROUTE) {
+                settings.route = Some(value.to_string());
+            } else if let Some(value) = text.strip_prefix(raceprint::SETTING_WEAPON) {
+                settings.weapon = Some(value.to_string());
+            } else if let Some(value) = text.strip_prefix(raceprint::SETTING_FALSESTART) {
+                settings.falsestart = Some(value.to_string());
+            } else if let Some((name, value)) = text.split_once(raceprint::X_REACHED_CHECKPOINT) {
+                if let Some(duration) = value.rsplit(' ').next().and_then(parse_seconds) {
+                    checkpoints.push((name_of(name), duration));
+                }
+            } else if let Some((name, run_time)) = text
+                .split_once(raceprint::X_FINISHED_IN)
+                .and_then(|(name, value)| Some((name, parse_seconds(value)?)))
+            {
+                let player = name_of(name);
+                runs.push(RaceRun {
+                    index: print.index,
+                    time: print.time,
+                    run_time: Some(run_time),
+                    checkpoints: take_checkpoints(&mut checkpoints, &player),
+                    settings: settings.clone(),
+                    is_record: false,
+                    is_personal_best: false,
+                    player,
+                });
+            } else if let Some(name) = raceprint::X_DID_NOT_FINISH
+                .iter()
+                .find_map(|suffix| text.strip_suffix(suffix))
+            {
+                let player = name_of(name);
+                runs.push(RaceRun {
+                    index: print.index,
+                    time: print.time,
+                    run_time: None,
+                    checkpoints: take_checkpoints(&mut checkpoints, &player),
+                    settings: settings.clone(),
+                    is_record: false,
+                    is_personal_best: false,
+                    player,
+                });
+            } else if let Some(run) = runs.last_mut().filter(|r| r.time == print.time) {
+                // record announcements follow the finish print
+                let text = text.to_lowercase();
+
+                if raceprint::NEW_RECORD.iter().any(|r| text.ends_with(r)) {
+                    run.is_record = true;
+                    run.is_personal_best = true;
+                } else if raceprint::PERSONAL_BEST.iter().any(|r| text.ends_with(r)) {
+                    run.is_personal_best = true;
+                }
+            }
+        }
+    }
+
+    runs
+}
+
+fn take_checkpoints(checkpoints: &mut Vec<(String, Duration)>, player: &str) -> Vec<Duration> {
+    let (taken, rest): (Vec<_>, Vec<_>) = checkpoints.drain(..).partition(|(p, _)| p == player);
+    *checkpoints = rest;
+    taken.into_iter().map(|(_, d)| d).collect()
+}
+
+// "12.345", "12.345s" or "12.345 seconds"
+fn parse_seconds(value: &str) -> Option<Duration> {
+    let value = value
+        .strip_suffix(" seconds")
+        .or_else(|| value.strip_suffix('s'))
+        .unwrap_or(value);
+
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+
+    let seconds: f64 = value.parse().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::qw::message::Print;
+    use crate::qw::prot::PrintId;
+
+    use super::*;
+
+    fn timed_print(secs: u64, content: &[u8]) -> TimedPrint {
+        timed_print_with_id(secs, PrintId::High, content)
+    }
+
+    fn timed_print_with_id(secs: u64, id: PrintId, content: &[u8]) -> TimedPrint {
+        TimedPrint {
+            index: secs as usize,
+            time: Duration::from_secs(secs),
+            print: Print {
+                id,
+                content: content.to_vec(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_seconds() {
+        assert_eq!(parse_seconds("12.5"), Some(Duration::from_millis(12500)));
+        assert_eq!(parse_seconds("12.5s"), Some(Duration::from_millis(12500)));
+        assert_eq!(
+            parse_seconds("12.5 seconds"),
+            Some(Duration::from_millis(12500))
+        );
+        assert_eq!(parse_seconds("soon"), None);
+        assert_eq!(parse_seconds("3rd"), None);
+        assert_eq!(parse_seconds("1e3"), None);
+        assert_eq!(parse_seconds("s"), None);
+    }
+
+    #[test]
+    fn test_race_runs_from_prints() {
+        let prints = [
+            timed_print(1, b"route: start - end\nweapon: none\nfalsestart: no\n"),
+            timed_print(4, b"XantoM reached checkpoint 1 in 2.250\n"),
+            timed_print(8, b"XantoM finished in 6.500 seconds\n"),
+            timed_print(8, b"\xee\xe5\xf7 record!\n"),
+            timed_print(20, b"Milton did not finish\n"),
+        ];
+
+        let settings = RaceSettings {
+            route: Some("start - end".to_string()),
+            weapon: Some("none".to_string()),
+            falsestart: Some("no".to_string()),
+        };
+
+        assert_eq!(
+            race_runs_from_prints(&prints),
+            vec![
+                RaceRun {
+                    index: 8,
+                    time: Duration::from_secs(8),
+                    player: "XantoM".to_string(),
+                    run_time: Some(Duration::from_millis(6500)),
+                    checkpoints: vec![Duration::from_millis(2250)],
+                    settings: settings.clone(),
+                    is_record: true,
+                    is_personal_best: true,
+                },
+                RaceRun {
+                    index: 20,
+                    time: Duration::from_secs(20),
+                    player: "Milton".to_string(),
+                    run_time: None,
+                    checkpoints: vec![],
+                    settings,
+                    is_record: false,
+                    is_personal_best: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_race_runs_ignores_lookalikes() {
+        let prints = [
+            timed_print_with_id(2, PrintId::Chat, b"foo: bar did not finish\n"),
+            timed_print_with_id(3, PrintId::Chat, b"foo: bar finished in 6.500\n"),
+            timed_print_with_id(4, PrintId::Medium, b"bar did not finish\n"),
+            timed_print(5, b"bar finished in 3rd\n"),
+            timed_print(6, b"bar false started\n"),
+        ];
+
+        assert_eq!(race_runs_from_prints(&prints), vec![]);
+    }
+
+    #[test]
+    fn test_race_runs() -> Result<()> {
+        assert_eq!(
+            race_runs(&read(
+                "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd"
+            )?),
+            vec![]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::duration::match_duration_from_seeking;
+use crate::frags::frags_per_player_name;
+use crate::player::Player;
+use crate::players::players;
+use crate::qw::frame;
+use crate::qw::prot::{Command, Target};
+use crate::validate::has_end_of_demo_print;
+
+const MAX_BODY_SIZE: usize = 0x10000;
+const RESYNC_CHAIN_LEN: usize = 3; // consecutive plausible frames required to resync
+const END_OF_DEMO: [u8; 17] = [
+    0x00, 0x06, 0x0B, 0x00, 0x00, 0x00, 0x02, 0x45, 0x6E, 0x64, 0x4F, 0x66, 0x44, 0x65, 0x6D, 0x6F,
+    0x00,
+]; // [duration] [all] [size] [disconnect] "EndOfDemo"
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameScan {
+    pub frames: Vec<Range<usize>>,
+    pub skipped: Vec<Range<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecoveredDemo {
+    pub skipped: Vec<Range<usize>>,
+    pub is_truncated: bool,
+    pub players: Option<Vec<Player>>, // none if the player list is unreadable
+    pub frags: HashMap<String, i32>,
+    pub duration: Option<Duration>, // match duration up to the last readable frame
+}
+
+// resyncs on the next plausible frame header after a bad frame
+pub fn scan_frames(data: &[u8]) -> FrameScan {
+    let mut scan = FrameScan::default();
+    let mut index = 0;
+
+    while index < data.len() {
+        if let Some(size) = plausible_frame_size(data, index) {
+            scan.frames.push(index..index + size);
+            index += size;
+            continue;
+        }
+
+        let next = (index + 1..data.len())
+            .find(|i| is_resync_point(data, *i))
+            .unwrap_or(data.len());
+        scan.skipped.push(index..next);
+        index = next;
+    }
+
+    scan
+}
+
+// valid frames only, terminated with an end of demo print
+pub fn repair(data: &[u8]) -> Vec<u8> {
+    repair_from_scan(data, &scan_frames(data))
+}
+
+fn repair_from_scan(data: &[u8], scan: &FrameScan) -> Vec<u8> {
+    let mut repaired: Vec<u8> = Vec::with_capacity(data.len() + END_OF_DEMO.len());
+
+    for range in scan.frames.iter() {
+        repaired.extend_from_slice(&data[range.clone()]);
+    }
+
+    if !has_end_of_demo_print(&repaired) {
+        repaired.extend_from_slice(&END_OF_DEMO);
+    }
+
+    repaired
+}
+
+// whatever is readable, fields that can not be read are left empty
+pub fn recover(data: &[u8]) -> RecoveredDemo {
+    let scan = scan_frames(data);
+    let repaired = repair_from_scan(data, &scan);
+
+    RecoveredDemo {
+        is_truncated: !has_end_of_demo_print(data),
+        players: players(&repaired).ok(),
+        frags: frags_per_player_name(&repaired),
+        duration: match_duration_from_seeking(&repaired).ok(),
+        skipped: scan.skipped,
+    }
+}
+
+fn plausible_frame_size(data: &[u8], index: usize) -> Option<usize> {
+    let info = frame::Info::from_data_and_index(data, index).ok()?;
+    let is_plausible = info.command == Command::Read
+        && info.target != Target::None
+        && info.body_size <= MAX_BODY_SIZE;
+    is_plausible.then_some(info.size)
+}
+
+fn is_resync_point(data: &[u8], index: usize) -> bool {
+    let mut index = index;
+
+    for _ in 0..RESYNC_CHAIN_LEN {
+        let Some(size) = plausible_frame_size(data, index) else {
+            return false;
+        };
+        index += size;
+
+        if index == data.len() {
+            return true;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::validate::is_valid;
+
+    use super::*;
+
+    const DEMO_PATH: &str = "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd";
+
+    #[test]
+    fn test_scan_frames() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        let scan = scan_frames(&data);
+        assert!(scan.skipped.is_empty());
+        assert_eq!(scan.frames.last().map(|f| f.end), Some(data.len()));
+
+        let mut corrupted = data.clone();
+        corrupted[200_000..200_500].fill(0xff);
+        let scan = scan_frames(&corrupted);
+        assert_eq!(scan.skipped.len(), 1);
+        assert!(scan.skipped[0].start <= 200_000);
+        assert!(scan.skipped[0].end >= 200_500);
+        assert!(scan.frames.len() > 1000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repair() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        assert_eq!(repair(&data), data);
+
+        let truncated = &data[..data.len() / 2];
+        assert!(!is_valid(truncated));
+
+        let repaired = repair(truncated);
+        assert!(is_valid(&repaired));
+        assert!(repaired.len() < truncated.len() + END_OF_DEMO.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recover() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        let mut corrupted = data[..data.len() / 2].to_vec();
+        corrupted[200_000..200_500].fill(0xff);
+
+        let recovered = recover(&corrupted);
+        assert!(recovered.is_truncated);
+        assert_eq!(recovered.skipped.len(), 2);
+        assert_eq!(recovered.skipped[1].end, corrupted.len());
+        assert_eq!(
+            recovered
+                .players
+                .unwrap_or_default()
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<String>>(),
+            vec!["KabÏÏm".to_string(), "eQu".to_string()]
+        );
+        assert!(!recovered.frags.is_empty());
+        let duration = recovered.duration.unwrap_or_default();
+        assert!(duration > Duration::ZERO);
+        assert!(duration < match_duration_from_seeking(&data)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recover_partial() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+
+        // clientinfo is lost and there are no ktxstats, frames after it are still readable
+        let mut corrupted = data[..data.len() / 2].to_vec();
+        corrupted[..4000].fill(0xff);
+
+        let recovered = recover(&corrupted);
+        assert_eq!(recovered.players, None);
+        assert!(recovered.duration.unwrap_or_default() > Duration::ZERO);
+        assert_eq!(recovered.skipped.first().map(|r| r.start), Some(0));
+
+        let recovered = recover(&[0xff; 100]);
+        assert_eq!(recovered.players, None);
+        assert!(recovered.frags.is_empty());
+        assert_eq!(recovered.skipped, vec![0..100]);
+
+        Ok(())
+    }
+}
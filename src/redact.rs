@@ -0,0 +1,682 @@
+use std::collections::HashMap;
+
+use bstr::ByteSlice;
+use quake_text::unicode;
+
+use crate::clientinfo::clientinfo_strings;
+use crate::qw::message::{message_refs, MessageRef};
+use crate::qw::primitives::numsize;
+use crate::qw::prot::{Command, HiddenMessage, PrintId};
+use crate::qw::{block, frame};
+use crate::userinfo::name_timeline;
+
+const REDACTED_IP: &[u8] = b"0.0.0.0";
+const AUTH_PRINT_INFIX: &[u8] = b" logged in as "; // "{name} logged in as {login}"
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RedactOptions {
+    pub remove_ips: bool,  // serverinfo, userinfo (*ip), prints and ktxstats
+    pub remove_auth: bool, // userinfo (*auth), login prints and ktxstats (login)
+    pub remove_chat: bool, // say, say_team and spectator chat
+    pub rename: HashMap<String, String>, // player name -> new name
+}
+
+impl Default for RedactOptions {
+    fn default() -> Self {
+        Self {
+            remove_ips: true,
+            remove_auth: true,
+            remove_chat: true,
+            rename: HashMap::new(),
+        }
+    }
+}
+
+struct Context<'a> {
+    options: &'a RedactOptions,
+    names: Vec<Vec<u8>>,             // all clients, to identify chat
+    rename: Vec<(Vec<u8>, Vec<u8>)>, // longest name first
+}
+
+impl<'a> Context<'a> {
+    fn new(data: &[u8], options: &'a RedactOptions) -> Self {
+        // initial clients (including spectators) and every later name change or join
+        let mut names: Vec<Vec<u8>> = clientinfo_strings(data)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|info| {
+                let bytes = unicode::to_bytestr(info);
+                info_value(&bytes, b"name")
+            })
+            .chain(
+                name_timeline(data)
+                    .changes
+                    .iter()
+                    .map(|c| unicode::to_bytestr(&c.name)),
+            )
+            .filter(|name| !name.is_empty())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut rename: Vec<(Vec<u8>, Vec<u8>)> = options
+            .rename
+            .iter()
+            .map(|(from, to)| (unicode::to_bytestr(from), unicode::to_bytestr(to)))
+            .filter(|(from, _)| !from.is_empty())
+            .collect();
+        rename.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(&b.0)));
+
+        Self {
+            options,
+            names,
+            rename,
+        }
+    }
+
+    fn is_chat(&self, content: &[u8]) -> bool {
+        self.names.iter().any(|name| {
+            [
+                [name.as_slice(), b": "].concat(),
+                [b"(", name.as_slice(), b"): "].concat(),
+                [b"[", name.as_slice(), b"]: "].concat(),
+            ]
+            .iter()
+            .any(|prefix| content.starts_with(prefix))
+        })
+    }
+
+    fn text(&self, value: &[u8]) -> Vec<u8> {
+        let mut value = value.to_vec();
+
+        if self.options.remove_ips {
+            value = scrub_ips(&value);
+        }
+
+        for (from, to) in &self.rename {
+            value = replace_name(&value, from, to);
+        }
+
+        value
+    }
+
+    fn info_string(&self, info: &[u8]) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![];
+
+        for (key, value) in info_pairs(info) {
+            if self.is_removed_key(key) {
+                continue;
+            }
+
+            for part in [key, self.info_value(key, value).as_slice()] {
+                result.push(b'\\');
+                result.extend_from_slice(part);
+            }
+        }
+
+        result
+    }
+
+    fn is_removed_key(&self, key: &[u8]) -> bool {
+        (self.options.remove_ips && key == b"*ip") || (self.options.remove_auth && key == b"*auth")
+    }
+
+    fn info_value(&self, key: &[u8], value: &[u8]) -> Vec<u8> {
+        if self.is_removed_key(key) {
+            return vec![];
+        }
+
+        if key == b"name" {
+            if let Some((_, to)) = self.rename.iter().find(|(from, _)| from == value) {
+                return to.clone();
+            }
+        }
+
+        match self.options.remove_ips {
+            true => scrub_ips(value),
+            false => value.to_vec(),
+        }
+    }
+
+    fn ktxstats(&self, json: &[u8]) -> Vec<u8> {
+        let mut json = json.to_vec();
+
+        if self.options.remove_ips {
+            json = scrub_ips(&json);
+        }
+
+        if self.options.remove_auth {
+            json = replace_json_values(&json, b"login", |_| vec![]);
+        }
+
+        json = replace_json_values(&json, b"name", |value| {
+            match self
+                .rename
+                .iter()
+                .find(|(from, _)| json_string(from) == value)
+            {
+                Some((_, to)) => json_string(to),
+                None => value.to_vec(),
+            }
+        });
+
+        json
+    }
+}
+
+// rewrites serverinfo, userinfo, prints and ktxstats, frame sizes are recomputed
+pub fn redact(data: &[u8], options: &RedactOptions) -> Vec<u8> {
+    let ctx = Context::new(data, options);
+    let mut result: Vec<u8> = Vec::with_capacity(data.len());
+    let mut demoinfo = DemoinfoFrames::default();
+    let mut index = 0;
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        index += info.size;
+
+        if info.command != Command::Read || info.body_size == 0 {
+            demoinfo.flush(data, &mut result);
+            result.extend_from_slice(&data[info.index..index]);
+        } else if info.is_hidden {
+            if !demoinfo.push(data, &info) {
+                demoinfo.flush(data, &mut result);
+                result.extend_from_slice(&data[info.index..index]);
+            } else if demoinfo.is_complete {
+                demoinfo.write(data, &ctx, &mut result);
+            }
+        } else {
+            demoinfo.flush(data, &mut result);
+            let body = redact_body(&data[info.body_range.clone()], &ctx);
+            write_frame(&mut result, &data[info.header_range], &body);
+        }
+    }
+
+    demoinfo.flush(data, &mut result);
+    result.extend_from_slice(&data[index..]);
+    result
+}
+
+fn redact_body(body: &[u8], ctx: &Context) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::with_capacity(body.len());
+    let mut messages = message_refs(body);
+
+    loop {
+        let from = messages.position();
+
+        // unknown messages end the readable part of the body, keep the rest as is
+        let Some(message) = messages.next() else {
+            result.extend_from_slice(&body[from..]);
+            break;
+        };
+
+        let original = &body[from..messages.position()];
+        result.extend_from_slice(&redact_message(message, original, ctx));
+    }
+
+    result
+}
+
+fn redact_message(message: MessageRef, original: &[u8], ctx: &Context) -> Vec<u8> {
+    let message_type = original[0];
+
+    match message {
+        MessageRef::Print(print) => {
+            if ctx.options.remove_chat && print.id == PrintId::Chat && ctx.is_chat(print.content) {
+                return vec![];
+            }
+            if ctx.options.remove_auth && is_auth_print(print.content) {
+                return vec![];
+            }
+            [
+                &[message_type, original[1]],
+                bstring(&ctx.text(print.content)).as_slice(),
+            ]
+            .concat()
+        }
+        MessageRef::CenterPrint(content) => {
+            [&[message_type], bstring(&ctx.text(content)).as_slice()].concat()
+        }
+        MessageRef::Stufftext(content) if ctx.options.remove_ips => {
+            [&[message_type], bstring(&scrub_ips(content)).as_slice()].concat()
+        }
+        MessageRef::Serverinfo(info) => [
+            &[message_type],
+            bstring(info.key).as_slice(),
+            &bstring(&ctx.info_value(info.key, info.value)),
+        ]
+        .concat(),
+        MessageRef::Setinfo(info) => [
+            &[message_type, info.player_number],
+            bstring(info.key).as_slice(),
+            &bstring(&ctx.info_value(info.key, info.value)),
+        ]
+        .concat(),
+        MessageRef::UpdateUserinfo(info) => [
+            &[message_type, info.player_number],
+            info.user_id.to_le_bytes().as_slice(),
+            &bstring(&ctx.info_string(info.userinfo)),
+        ]
+        .concat(),
+        _ => original.to_vec(),
+    }
+}
+
+// ktxstats is split into demoinfo blocks, one per hidden frame, numbered down to 0
+#[derive(Default)]
+struct DemoinfoFrames {
+    frames: Vec<frame::Info>,
+    content: Vec<u8>,
+    is_complete: bool,
+}
+
+impl DemoinfoFrames {
+    fn push(&mut self, data: &[u8], info: &frame::Info) -> bool {
+        let body = &data[info.body_range.clone()];
+        let Ok(block) = block::Info::try_from(body) else {
+            return false;
+        };
+
+        if block.hidden_message != HiddenMessage::Demoinfo || block.total_size != body.len() {
+            return false;
+        }
+
+        self.frames.push(info.clone());
+        self.content.extend_from_slice(&body[block::HEADER_SIZE..]);
+        self.is_complete = block.number == 0;
+        true
+    }
+
+    fn flush(&mut self, data: &[u8], result: &mut Vec<u8>) {
+        for info in self.frames.drain(..) {
+            result.extend_from_slice(&data[info.index..info.index + info.size]);
+        }
+        self.content.clear();
+        self.is_complete = false;
+    }
+
+    // one block per original frame, headers (and durations) are kept as is
+    fn write(&mut self, data: &[u8], ctx: &Context, result: &mut Vec<u8>) {
+        let content = ctx.ktxstats(&self.content);
+        let count = self.frames.len();
+        let chunk_size = content.len().div_ceil(count.max(1));
+
+        for (number, frame) in self.frames.iter().enumerate() {
+            let from = (number * chunk_size).min(content.len());
+            let chunk = &content[from..(from + chunk_size).min(content.len())];
+            let block_number = (count - 1 - number) as u16;
+            let body = [
+                ((chunk.len() + numsize::SHORT) as u32)
+                    .to_le_bytes()
+                    .as_slice(),
+                &(HiddenMessage::Demoinfo as u16).to_le_bytes(),
+                &block_number.to_le_bytes(),
+                chunk,
+            ]
+            .concat();
+            write_frame(result, &data[frame.header_range.clone()], &body);
+        }
+
+        self.frames.clear();
+        self.content.clear();
+        self.is_complete = false;
+    }
+}
+
+fn is_auth_print(content: &[u8]) -> bool {
+    content.find(AUTH_PRINT_INFIX).is_some()
+}
+
+fn write_frame(result: &mut Vec<u8>, header: &[u8], body: &[u8]) {
+    result.extend_from_slice(header);
+
+    // body size is the last long of the header
+    let size_offset = result.len() - numsize::LONG;
+    result[size_offset..].copy_from_slice(&(body.len() as u32).to_le_bytes());
+    result.extend_from_slice(body);
+}
+
+fn bstring(value: &[u8]) -> Vec<u8> {
+    [value, &[0]].concat()
+}
+
+fn info_pairs(info: &[u8]) -> Vec<(&[u8], &[u8])> {
+    if info.is_empty() {
+        return vec![];
+    }
+
+    let parts: Vec<&[u8]> = info
+        .strip_prefix(b"\\")
+        .unwrap_or(info)
+        .split(|b| *b == b'\\')
+        .collect();
+    parts
+        .chunks(2)
+        .map(|pair| (pair[0], pair.get(1).copied().unwrap_or_default()))
+        .collect()
+}
+
+fn info_value(info: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+    info_pairs(info)
+        .into_iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.to_vec())
+}
+
+// replaces whole names only, e.g. "bro" in "bro's rocket" but not in "brother"
+fn replace_name(value: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+    let is_boundary = |index: Option<&u8>| index.is_none_or(|b| !b.is_ascii_alphanumeric());
+    let mut result: Vec<u8> = Vec::with_capacity(value.len());
+    let mut offset = 0;
+
+    for index in value.find_iter(from) {
+        if index < offset {
+            continue;
+        }
+
+        let before = index.checked_sub(1).and_then(|i| value.get(i));
+        let after = value.get(index + from.len());
+
+        if is_boundary(before) && is_boundary(after) {
+            result.extend_from_slice(&value[offset..index]);
+            result.extend_from_slice(to);
+            offset = index + from.len();
+        }
+    }
+
+    result.extend_from_slice(&value[offset..]);
+    result
+}
+
+fn scrub_ips(value: &[u8]) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::with_capacity(value.len());
+    let mut index = 0;
+
+    while index < value.len() {
+        let is_start = index == 0 || !is_ip_byte(value[index - 1]);
+
+        if let Some(len) = is_start.then(|| ipv4_len(&value[index..])).flatten() {
+            result.extend_from_slice(REDACTED_IP);
+            index += len;
+        } else {
+            result.push(value[index]);
+            index += 1;
+        }
+    }
+
+    result
+}
+
+fn is_ip_byte(byte: u8) -> bool {
+    byte.is_ascii_digit() || byte == b'.'
+}
+
+fn ipv4_len(value: &[u8]) -> Option<usize> {
+    let mut index = 0;
+
+    for group in 0..4 {
+        if group > 0 {
+            if value.get(index) != Some(&b'.') {
+                return None;
+            }
+            index += 1;
+        }
+
+        let digits = value[index..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+
+        if !(1..=3).contains(&digits) {
+            return None;
+        }
+        index += digits;
+    }
+
+    // "1.2.3.4." at end of sentence is fine, "1.2.3.4.5" is not an ip
+    let is_end = match value.get(index) {
+        Some(b) if b.is_ascii_digit() => false,
+        Some(b'.') => !value.get(index + 1).is_some_and(|b| b.is_ascii_digit()),
+        _ => true,
+    };
+    is_end.then_some(index)
+}
+
+// string values of the given key, e.g. "login": "foo"
+fn replace_json_values(json: &[u8], key: &[u8], f: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let needle = [b"\"", key, b"\": \""].concat();
+    let mut result: Vec<u8> = Vec::with_capacity(json.len());
+    let mut offset = 0;
+
+    while let Some(range) = json[offset..]
+        .find(&needle)
+        .map(|i| offset + i + needle.len())
+        .and_then(|from| json_string_end(json, from).map(|to| from..to))
+    {
+        result.extend_from_slice(&json[offset..range.start]);
+        result.extend_from_slice(&f(&json[range.clone()]));
+        offset = range.end;
+    }
+
+    result.extend_from_slice(&json[offset..]);
+    result
+}
+
+fn json_string_end(json: &[u8], from: usize) -> Option<usize> {
+    let mut index = from;
+
+    while let Some(byte) = json.get(index) {
+        match byte {
+            b'\\' => index += 2,
+            b'"' => return Some(index),
+            _ => index += 1,
+        }
+    }
+
+    None
+}
+
+// quake text as encoded by ktxstats, e.g. "ä"
+fn json_string(value: &[u8]) -> Vec<u8> {
+    let mut result: Vec<u8> = vec![];
+
+    for byte in value {
+        match byte {
+            b'"' => result.extend_from_slice(b"\\\""),
+            b'\\' => result.extend_from_slice(b"\\\\"),
+            0x20..=0x7e => result.push(*byte),
+            _ => result.extend_from_slice(format!("\\u{:04X}", byte).as_bytes()),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::prints::timed_prints;
+    use crate::recovery::scan_frames;
+    use crate::{demo_duration, frags_per_player_name, is_valid, ktxstats_v3, players, server};
+
+    use super::*;
+    use crate::testdemo::{hidden, print, to_all};
+
+    const DEMO_PATH: &str = "tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd";
+
+    fn chat_count(data: &[u8]) -> usize {
+        timed_prints(data)
+            .iter()
+            .filter(|p| p.print.id == PrintId::Chat)
+            .count()
+    }
+
+    #[test]
+    fn test_redact() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        let redacted = redact(&data, &RedactOptions::default());
+
+        // still plays
+        assert!(is_valid(&redacted));
+        assert!(scan_frames(&redacted).skipped.is_empty());
+        assert_eq!(demo_duration(&redacted)?, demo_duration(&data)?);
+        assert_eq!(players(&redacted)?, players(&data)?);
+
+        assert!(data.find(b"46.227.68.148").is_some());
+        assert!(redacted.find(b"46.227.68.148").is_none());
+        assert_eq!(server(&redacted)?.ip, "0.0.0.0");
+
+        assert_eq!(chat_count(&data), 171);
+        assert_eq!(chat_count(&redacted), 1); // "Server starts recording"
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_redact_rename() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        let options = RedactOptions {
+            rename: HashMap::from([("HoLy".to_string(), "player1".to_string())]),
+            ..Default::default()
+        };
+        let redacted = redact(&data, &options);
+
+        let names: Vec<String> = players(&redacted)?.into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["äáçï".to_string(), "player1".to_string()]);
+        assert_eq!(
+            frags_per_player_name(&redacted).get("player1"),
+            frags_per_player_name(&data).get("HoLy")
+        );
+        assert!(ktxstats_v3(&redacted)?
+            .players
+            .iter()
+            .any(|p| p.name == "player1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_redact_chat_after_rename_and_join() {
+        let userinfo = |slot: u8, name: &[u8]| {
+            [
+                &[0x28, slot, 1, 0, 0, 0],
+                b"\\name\\".as_slice(),
+                name,
+                &[0],
+            ]
+            .concat()
+        };
+        let data = [
+            to_all(
+                0,
+                &[b"\x09cmd spawn\n\0".to_vec(), userinfo(0, b"FOO")].concat(),
+            ),
+            to_all(100, &userinfo(0, b"BAR")),
+            to_all(100, &userinfo(1, b"BAZ")),
+            to_all(100, &print(3, b"FOO: hi\n")),
+            to_all(100, &print(3, b"BAR: hello\n")),
+            to_all(100, &print(3, b"(BAZ): gl\n")),
+            to_all(100, &print(2, b"BAZ entered the game\n")),
+        ]
+        .concat();
+
+        assert_eq!(chat_count(&data), 3);
+        let redacted = redact(&data, &RedactOptions::default());
+        assert_eq!(chat_count(&redacted), 0);
+        assert!(redacted.find(b"BAZ entered the game").is_some());
+    }
+
+    #[test]
+    fn test_redact_demoinfo_and_login() {
+        let demoinfo = |duration: u8, number: u16, content: &[u8]| {
+            let block = [
+                ((content.len() + 2) as u32).to_le_bytes().as_slice(),
+                &(HiddenMessage::Demoinfo as u16).to_le_bytes(),
+                &number.to_le_bytes(),
+                content,
+            ]
+            .concat();
+            let mut frame = hidden(&block);
+            frame[0] = duration;
+            frame
+        };
+        let data = [
+            to_all(100, &print(2, b"FOO logged in as foo_login\n")),
+            demoinfo(200, 1, br#"{"ip": "10.0.0.1", "#),
+            demoinfo(200, 0, br#""login": "foo_login"}"#),
+            to_all(100, &print(2, b"The match is over\n")),
+        ]
+        .concat();
+
+        let redacted = redact(&data, &RedactOptions::default());
+        assert_eq!(
+            demo_duration(&redacted).unwrap(),
+            demo_duration(&data).unwrap()
+        );
+        assert!(scan_frames(&redacted).skipped.is_empty());
+        assert!(data.find(b"foo_login").is_some());
+        assert!(redacted.find(b"foo_login").is_none());
+        assert!(redacted.find(b"10.0.0.1").is_none());
+    }
+
+    #[test]
+    fn test_redact_nothing() -> Result<()> {
+        let data = read(DEMO_PATH)?;
+        let options = RedactOptions {
+            remove_ips: false,
+            remove_auth: false,
+            remove_chat: false,
+            rename: HashMap::new(),
+        };
+        assert_eq!(redact(&data, &options), data);
+        assert_eq!(redact(&[], &options), Vec::<u8>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scrub_ips() {
+        assert_eq!(
+            scrub_ips(b"from 46.227.68.148:28501."),
+            b"from 0.0.0.0:28501.".to_vec()
+        );
+        assert_eq!(
+            scrub_ips(b"1.2.3.4.5 1.2.3 1234.1.1.1"),
+            b"1.2.3.4.5 1.2.3 1234.1.1.1".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_replace_name() {
+        assert_eq!(
+            replace_name(b"bro rides bro's rocket", b"bro", b"foo"),
+            b"foo rides foo's rocket".to_vec()
+        );
+        assert_eq!(
+            replace_name(b"brother", b"bro", b"foo"),
+            b"brother".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_info_string() {
+        let options = RedactOptions {
+            rename: HashMap::from([("XantoM".to_string(), "player1".to_string())]),
+            ..Default::default()
+        };
+        let ctx = Context::new(&[], &options);
+        assert_eq!(
+            ctx.info_string(br"\name\XantoM\*ip\10.0.0.1\*auth\xantom\team\red"),
+            br"\name\player1\team\red".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string(b"a\"b"), br#"a\"b"#.to_vec());
+        assert_eq!(json_string(&[0xe4, 0x2e]), br"\u00E4.".to_vec());
+    }
+}
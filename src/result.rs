@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Result;
+use bstr::ByteSlice;
+use quake_text::bytestr::to_unicode;
+
+use crate::aborted::is_aborted;
+use crate::clients::player_clients;
+use crate::flags::{ctf_stats, FlagColor};
+use crate::hoonymode::{hoonymode_points, is_hoonymode};
+use crate::prints::timed_prints;
+use crate::qw::prot::PrintId;
+use crate::rounds::rounds;
+use crate::team::Team;
+use crate::userinfo::name_timeline;
+use crate::validate::has_end_of_demo_print;
+use crate::{matchdate, players, serverinfo, teams};
+
+const MATCH_OVER: &[u8] = b"The match is over";
+const PLAYER_LEFT_SUFFIXES: [&str; 2] = [" dropped", " left the game"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scoring {
+    Frags,
+    Captures,
+    Rounds,
+    Points,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Score {
+    pub name: String, // team or player
+    pub score: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchResult {
+    pub scoring: Scoring,
+    pub scores: Vec<Score>,   // highest first
+    pub winners: Vec<String>, // empty unless completed, several if draw
+    pub margin: i32,
+    pub is_completed: bool,
+    pub is_draw: bool,
+    pub is_aborted: bool,
+    pub is_forfeit: bool,
+}
+
+pub fn result(data: &[u8]) -> Result<MatchResult> {
+    let (scoring, scores) = scores(data)?;
+    let mut scores: Vec<Score> = scores
+        .into_iter()
+        .map(|(name, score)| Score { name, score })
+        .collect();
+    scores.sort_by(|a, b| b.score.cmp(&a.score).then(a.name.cmp(&b.name)));
+
+    let is_aborted = is_aborted(data);
+    let is_completed =
+        !is_aborted && has_end_of_demo_print(data) && data.find(MATCH_OVER).is_some();
+    let top_score = scores.first().map(|s| s.score);
+    let margin = match scores.as_slice() {
+        [first, second, ..] => first.score.saturating_sub(second.score),
+        _ => 0,
+    };
+    let is_draw = is_completed && scores.len() > 1 && margin == 0;
+    let winners = match is_completed {
+        true => scores
+            .iter()
+            .filter(|s| Some(s.score) == top_score)
+            .map(|s| s.name.clone())
+            .collect(),
+        false => vec![],
+    };
+    let is_forfeit = !is_completed && !is_aborted && has_player_left(data);
+
+    Ok(MatchResult {
+        scoring,
+        scores,
+        winners,
+        margin,
+        is_completed,
+        is_draw,
+        is_aborted,
+        is_forfeit,
+    })
+}
+
+fn scores(data: &[u8]) -> Result<(Scoring, HashMap<String, i32>)> {
+    let rounds = rounds(data);
+    if !rounds.is_empty() {
+        let mut wins: HashMap<String, i32> = HashMap::new();
+        for team in teams(data)? {
+            wins.insert(team.name, 0);
+        }
+        for winner in rounds.into_iter().filter_map(|r| r.winner) {
+            *wins.entry(winner).or_default() += 1;
+        }
+        return Ok((Scoring::Rounds, wins));
+    }
+
+    if is_hoonymode(data) {
+        let score = hoonymode_points(data)
+            .pop()
+            .map(|p| p.score)
+            .unwrap_or_default();
+        return Ok((Scoring::Points, score));
+    }
+
+    let settings = serverinfo(data)?;
+
+    if settings.mode.as_deref() == Some("ctf") {
+        let captures = ctf_stats(data).captures;
+        let teams = teams(data)?;
+        let mut scores: HashMap<String, i32> = HashMap::new();
+        for team in teams.iter() {
+            scores.insert(team.name.clone(), 0);
+        }
+        for (color, count) in captures {
+            *scores.entry(flag_team(&teams, color)).or_default() += count as i32;
+        }
+        return Ok((Scoring::Captures, scores));
+    }
+
+    if settings.teamplay.is_some_and(|t| t > 0) {
+        let teams = teams(data)?;
+        if teams.len() > 1 {
+            return Ok((
+                Scoring::Frags,
+                teams.into_iter().map(|t| (t.name, t.frags)).collect(),
+            ));
+        }
+    }
+
+    Ok((
+        Scoring::Frags,
+        players(data)?
+            .into_iter()
+            .map(|p| (p.name, p.frags))
+            .collect(),
+    ))
+}
+
+// team wearing the flag color, else team named after it
+fn flag_team(teams: &[Team], color: FlagColor) -> String {
+    teams
+        .iter()
+        .find(|t| t.color[1] == color.bottom_color())
+        .or_else(|| teams.iter().find(|t| t.name == color.name()))
+        .map(|t| t.name.clone())
+        .unwrap_or(color.name().to_string())
+}
+
+// player left after match start and is absent at the end, e.g. "XantoM dropped"
+fn has_player_left(data: &[u8]) -> bool {
+    let Some(match_start) = data.find(matchdate::MATCHDATE_NEEDLE) else {
+        return false;
+    };
+
+    let present: HashSet<String> = name_timeline(data)
+        .names_at(usize::MAX)
+        .into_values()
+        .collect();
+    let absent: Vec<String> = player_clients(data)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| c.name)
+        .filter(|name| !present.contains(name))
+        .collect();
+
+    if absent.is_empty() {
+        return false;
+    }
+
+    timed_prints(data)
+        .iter()
+        .filter(|p| p.index >= match_start && p.print.id == PrintId::High)
+        .any(|p| {
+            let text = to_unicode(p.print.content.trim_end());
+            absent.iter().any(|name| {
+                PLAYER_LEFT_SUFFIXES
+                    .iter()
+                    .any(|s| text.strip_prefix(name.as_str()) == Some(*s))
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testdemo::{print, to_all};
+
+    #[test]
+    fn test_result() -> Result<()> {
+        {
+            let res = result(&read(
+                "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd",
+            )?)?;
+            assert_eq!(res.scoring, Scoring::Frags);
+            assert_eq!(
+                res.scores,
+                vec![
+                    Score {
+                        name: "KabÏÏm".to_string(),
+                        score: 20,
+                    },
+                    Score {
+                        name: "eQu".to_string(),
+                        score: 19,
+                    },
+                ]
+            );
+            assert_eq!(res.winners, vec!["KabÏÏm".to_string()]);
+            assert_eq!(res.margin, 1);
+            assert!(res.is_completed);
+            assert!(!res.is_draw);
+            assert!(!res.is_aborted);
+            assert!(!res.is_forfeit);
+        }
+        {
+            let res = result(&read(
+                "tests/files/2on2_sf_vs_red[frobodm2]220104-0915.mvd",
+            )?)?;
+            assert_eq!(res.scores[0].name, "red");
+            assert_eq!(res.winners, Vec::<String>::new());
+            assert!(res.is_aborted);
+            assert!(!res.is_completed);
+        }
+        {
+            let res = result(&read("tests/files/ffa_5[dm4]20240501-1229.mvd")?)?;
+            assert_eq!(res.scores.len(), 5);
+            assert_eq!(res.scores[0].name, "/ tincan");
+            assert!(res.is_aborted);
+        }
+        {
+            let res = result(&read(
+                "tests/files/wipeout_red_vs_blue[q3dm6qw]20240406-2028.mvd",
+            )?)?;
+            assert_eq!(res.scoring, Scoring::Rounds);
+            assert_eq!(res.winners, vec!["blue".to_string()]);
+            assert_eq!(res.margin, 5);
+            assert!(res.is_completed);
+        }
+        {
+            let res = result(&read(
+                "tests/files/1on1_milton_vs_mushi[tron]20240616-1719.mvd",
+            )?)?;
+            assert_eq!(res.scoring, Scoring::Points);
+            assert_eq!(res.scores[0].name, "Milton");
+            assert_eq!(res.margin, 6);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_flag_team() {
+        let team = |name: &str, color: [u8; 2]| Team {
+            name: name.to_string(),
+            color,
+            ..Default::default()
+        };
+        let teams = [team("sf", [13, 13]), team("red", [0, 4])];
+        assert_eq!(flag_team(&teams, FlagColor::Red), "red");
+        assert_eq!(flag_team(&teams, FlagColor::Blue), "sf");
+        assert_eq!(flag_team(&[team("blue", [0, 0])], FlagColor::Blue), "blue");
+        assert_eq!(flag_team(&[], FlagColor::Red), "red");
+    }
+
+    #[test]
+    fn test_has_player_left() {
+        let userinfo = |slot: u8, info: &[u8]| [&[0x28, slot, 1, 0, 0, 0], info, &[0]].concat();
+        let demo = |messages: &[Vec<u8>]| {
+            [
+                to_all(
+                    0,
+                    &[
+                        b"\x09cmd spawn\n\0".to_vec(),
+                        userinfo(0, b"\\name\\FOO"),
+                        userinfo(1, b"\\name\\BAR"),
+                        userinfo(2, b"\\name\\SPEC\\*spectator\\1"),
+                    ]
+                    .concat(),
+                ),
+                to_all(100, &print(2, b"matchdate: 2024-01-01 12:00:00 CET\n")),
+                to_all(100, &messages.concat()),
+            ]
+            .concat()
+        };
+
+        let bar_left = print(2, b"BAR left the game\n");
+        assert!(has_player_left(&demo(&[
+            bar_left.clone(),
+            userinfo(1, b"")
+        ])));
+        assert!(!has_player_left(&demo(&[
+            bar_left.clone(),
+            userinfo(1, b""),
+            userinfo(1, b"\\name\\BAR"),
+        ])));
+        assert!(!has_player_left(&demo(&[
+            print(2, b"SPEC left the game\n"),
+            userinfo(2, b""),
+        ])));
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use quake_text::bytestr::{to_ascii, to_unicode};
+
+use crate::qw::frame;
+use crate::qw::message::{messages, Message};
+use crate::qw::prot::{PrintId, Target};
+use crate::scoreboard::scoreboard_timeline;
+use crate::userinfo::name_timeline;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Survivor {
+    pub name: String,
+    pub health: u32,
+    pub armor: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LastManStanding {
+    pub time: Duration,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Round {
+    pub number: u32,
+    pub start: Duration,
+    pub end: Option<Duration>,
+    pub winner: Option<String>,
+    pub survivors: Vec<Survivor>,
+    pub frags: HashMap<String, i32>,
+    pub last_man_standing: Vec<LastManStanding>,
+}
+
+// KTX wipeout / clan arena centerprints and prints (as ascii)
+const ROUND_PREFIX: &str = "round ";
+const ROUND_START: &str = "FIGHT!";
+const ROUND_WINNER_PREFIX: &str = "Team ";
+const ROUND_WINNER_SUFFIXES: [&str; 2] = [" wins the round!", " wins the series!"];
+const SURVIVOR_INFIX: &str = " had ";
+const LAST_MAN_STANDING: &str = "stay alive!";
+
+pub fn rounds(data: &[u8]) -> Vec<Round> {
+    let names = name_timeline(data);
+    let scoreboard = scoreboard_timeline(data);
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut rounds: Vec<Round> = vec![];
+    let mut number: u32 = 0;
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += info.duration;
+
+        if info.body_size == 0 || info.is_hidden {
+            index += info.size;
+            continue;
+        }
+
+        let time = Duration::from_millis(total_ms as u64);
+
+        for message in messages(&data[info.body_range.clone()]) {
+            match message {
+                Message::CenterPrint(content) => {
+                    let lines: Vec<String> = content
+                        .split(|b| *b == b'\n')
+                        .map(|line| to_ascii(line).trim().to_string())
+                        .filter(|line| !line.is_empty())
+                        .collect();
+                    let Some(text) = lines.first().map(|l| l.as_str()) else {
+                        continue;
+                    };
+
+                    if let Some(value) = text.strip_prefix(ROUND_PREFIX) {
+                        number = value.parse().unwrap_or(number);
+                    } else if text == ROUND_START && info.target == Target::All {
+                        rounds.push(Round {
+                            number,
+                            start: time,
+                            ..Default::default()
+                        });
+                    } else if let Some(round) = rounds.last_mut().filter(|r| r.end.is_none()) {
+                        if let Some(winner) = winner_from_centerprint(text) {
+                            round.end = Some(time);
+                            round.winner = Some(winner);
+                        } else if lines.iter().any(|l| l == LAST_MAN_STANDING) {
+                            let Some(name) = info
+                                .player_number(data)
+                                .and_then(|n| names.names_at(info.index).remove(&n))
+                            else {
+                                continue;
+                            };
+
+                            if !round.last_man_standing.iter().any(|l| l.name == name) {
+                                round.last_man_standing.push(LastManStanding { time, name });
+                            }
+                        }
+                    }
+                }
+                Message::Print(p) if p.id == PrintId::High => {
+                    // survivors are printed after the round has ended
+                    let Some(round) = rounds.last_mut().filter(|r| r.end.is_some()) else {
+                        continue;
+                    };
+
+                    let content = p.content.strip_suffix(b"\n").unwrap_or(&p.content);
+                    if let Some(survivor) = survivor_from_print(content) {
+                        round.survivors.push(survivor);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        index += info.size;
+    }
+
+    // frags per round from scoreboard, named by the player in the slot at the time of each update
+    for round in rounds.iter_mut() {
+        let end = round.end.unwrap_or(Duration::from_millis(total_ms as u64));
+        let mut previous = scoreboard.frags_at(round.start);
+
+        for update in scoreboard
+            .updates
+            .iter()
+            .filter(|u| u.time > round.start && u.time <= end)
+        {
+            let delta = update.frags - previous.insert(update.number, update.frags).unwrap_or(0);
+
+            if let Some(name) = names.names_at(update.index).get(&update.number) {
+                *round.frags.entry(name.clone()).or_default() += delta;
+            }
+        }
+
+        round.frags.retain(|_, frags| *frags != 0);
+    }
+
+    rounds
+}
+
+// "Team [blue] wins the round!"
+fn winner_from_centerprint(text: &str) -> Option<String> {
+    let team = text.strip_prefix(ROUND_WINNER_PREFIX)?;
+    let team = ROUND_WINNER_SUFFIXES
+        .iter()
+        .find_map(|suffix| team.strip_suffix(suffix))?;
+    Some(team.trim_matches(['[', ']']).to_string())
+}
+
+// "<name> had <health>/<armor>"
+fn survivor_from_print(content: &[u8]) -> Option<Survivor> {
+    let text = to_ascii(content);
+    let (name, values) = text.rsplit_once(SURVIVOR_INFIX)?;
+    let (health, armor) = values.split_once('/')?;
+
+    Some(Survivor {
+        name: to_unicode(&content[..name.len()]),
+        health: health.parse().ok()?,
+        armor: armor.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testdemo::to_all;
+
+    #[test]
+    fn test_survivor_from_print() {
+        assert_eq!(
+            survivor_from_print(b"Kalle Dangerous had 155/89"),
+            Some(Survivor {
+                name: "Kalle Dangerous".to_string(),
+                health: 155,
+                armor: 89,
+            })
+        );
+        assert_eq!(survivor_from_print(b"XantoM had a good time"), None);
+    }
+
+    #[test]
+    fn test_rounds() -> Result<()> {
+        assert_eq!(
+            rounds(&read(
+                "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd"
+            )?),
+            vec![]
+        );
+
+        let rounds = rounds(&read(
+            "tests/files/wipeout_red_vs_blue[q3dm6qw]20240406-2028.mvd",
+        )?);
+        assert_eq!(rounds.len(), 5);
+        assert_eq!(
+            rounds.iter().map(|r| r.number).collect::<Vec<u32>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert!(rounds.iter().all(|r| r.winner == Some("blue".to_string())));
+
+        let round = &rounds[0];
+        assert_eq!(round.start, Duration::from_millis(18632));
+        assert_eq!(round.end, Some(Duration::from_millis(61519)));
+        assert_eq!(
+            round.survivors,
+            vec![
+                Survivor {
+                    name: "Kalle Dangerous".to_string(),
+                    health: 155,
+                    armor: 89,
+                },
+                Survivor {
+                    name: "j0rmund".to_string(),
+                    health: 35,
+                    armor: 59,
+                },
+            ]
+        );
+        assert_eq!(
+            round.frags,
+            HashMap::from([
+                ("z0mbie90".to_string(), 5),
+                ("Kalle Dangerous".to_string(), 4),
+                ("j0rmund".to_string(), 4),
+                ("luòñ".to_string(), 13),
+                ("grotzky".to_string(), 6),
+            ])
+        );
+        assert_eq!(round.last_man_standing.len(), 3);
+        assert_eq!(
+            round.last_man_standing[0],
+            LastManStanding {
+                time: Duration::from_millis(22580),
+                name: "luòñ".to_string(),
+            }
+        );
+        assert_eq!(rounds[4].end, Some(Duration::from_millis(224403)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rounds_frags_after_rename() {
+        let centerprint = |text: &[u8]| [&[26], text, &[0]].concat();
+        let userinfo = |name: &[u8]| [b"\x28\x01\x00\x00\x00\x00\\name\\", name, &[0]].concat();
+        let data = [
+            to_all(0, &userinfo(b"FOO")),
+            to_all(100, &centerprint(b"FIGHT!")),
+            to_all(100, &[14, 1, 1, 0]),
+            to_all(100, &centerprint(b"Team [red] wins the round!")),
+            to_all(100, &userinfo(b"BAR")),
+            to_all(100, &centerprint(b"FIGHT!")),
+            to_all(100, &[14, 1, 3, 0]),
+            to_all(100, &centerprint(b"Team [red] wins the round!")),
+        ]
+        .concat();
+
+        assert_eq!(
+            rounds(&data)
+                .into_iter()
+                .map(|r| r.frags)
+                .collect::<Vec<_>>(),
+            vec![
+                HashMap::from([("FOO".to_string(), 1)]),
+                HashMap::from([("BAR".to_string(), 2)]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::qw::frame;
+use crate::qw::message::{messages, Message};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreUpdate {
+    pub index: usize,
+    pub time: Duration,
+    pub number: u8,
+    pub frags: i32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreboardTimeline {
+    pub updates: Vec<ScoreUpdate>,
+}
+
+impl ScoreboardTimeline {
+    pub fn frags_at(&self, time: Duration) -> HashMap<u8, i32> {
+        let mut frags: HashMap<u8, i32> = HashMap::new();
+
+        for update in self.updates.iter().take_while(|u| u.time <= time) {
+            frags.insert(update.number, update.frags);
+        }
+
+        frags
+    }
+
+    pub fn final_frags(&self) -> HashMap<u8, i32> {
+        let mut frags: HashMap<u8, i32> = HashMap::new();
+
+        for update in self.updates.iter() {
+            frags.insert(update.number, update.frags);
+        }
+
+        frags
+    }
+
+    pub fn updates_for(&self, number: u8) -> Vec<ScoreUpdate> {
+        self.updates
+            .iter()
+            .filter(|u| u.number == number)
+            .cloned()
+            .collect()
+    }
+}
+
+pub fn scoreboard_timeline(data: &[u8]) -> ScoreboardTimeline {
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut current: HashMap<u8, i32> = HashMap::new();
+    let mut updates: Vec<ScoreUpdate> = vec![];
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += info.duration;
+
+        if info.body_size > 0 && !info.is_hidden {
+            let messages = messages(&data[info.body_range.clone()]);
+
+            // frags are reset when a slot is cleared, keep value from before
+            let cleared_slots: Vec<u8> = messages
+                .iter()
+                .filter_map(|m| match m {
+                    Message::UpdateUserinfo(u) if u.userinfo.is_empty() => Some(u.player_number),
+                    _ => None,
+                })
+                .collect();
+
+            for message in messages {
+                let Message::UpdateFrags(u) = message else {
+                    continue;
+                };
+
+                let frags = u.frags as i32;
+
+                if cleared_slots.contains(&u.player_number)
+                    || current.get(&u.player_number) == Some(&frags)
+                {
+                    continue;
+                }
+
+                current.insert(u.player_number, frags);
+                updates.push(ScoreUpdate {
+                    index: info.index,
+                    time: Duration::from_millis(total_ms as u64),
+                    number: u.player_number,
+                    frags,
+                });
+            }
+        }
+
+        index += info.size;
+    }
+
+    ScoreboardTimeline { updates }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_scoreboard_timeline() -> Result<()> {
+        {
+            let demo_data = read("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")?;
+            let timeline = scoreboard_timeline(&demo_data);
+
+            assert_eq!(
+                timeline.final_frags(),
+                HashMap::from([(0, 19), (1, 0), (2, 20)])
+            );
+            assert_eq!(
+                timeline.frags_at(Duration::from_secs(60)),
+                HashMap::from([(0, 6), (1, 0), (2, 6)])
+            );
+            assert_eq!(timeline.updates_for(2).len(), 21);
+            assert_eq!(
+                timeline.updates_for(2).last(),
+                Some(&ScoreUpdate {
+                    index: 696005,
+                    time: Duration::from_millis(187_169),
+                    number: 2,
+                    frags: 20,
+                })
+            );
+        }
+
+        {
+            let demo_data = read("tests/files/ffa_5[dm4]20240501-1229.mvd")?;
+            let timeline = scoreboard_timeline(&demo_data);
+
+            assert_eq!(
+                timeline.final_frags(),
+                HashMap::from([
+                    (0, 0),
+                    (1, 4),
+                    (2, 0),
+                    (3, 6),
+                    (4, 0),
+                    (5, 5),
+                    (6, 8),
+                    (7, 6),
+                ])
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 use ktxstats::v3::KtxstatsV3;
 
 use crate::ktxstats_v3;
@@ -8,6 +8,7 @@ pub fn server(data: &[u8]) -> Result<Server> {
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Server {
     pub hostname: String,
     pub address: String,
@@ -34,6 +35,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::Error;
 
     #[test]
     fn test_server() -> Result<()> {
@@ -51,10 +53,7 @@ mod tests {
         }
         {
             let demo_data = read("tests/files/wipeout_red_vs_blue[q3dm6qw]20240406-2028.mvd")?;
-            assert_eq!(
-                server(&demo_data).unwrap_err().to_string(),
-                "ktxstats not found"
-            );
+            assert!(matches!(server(&demo_data), Err(Error::NoKtxstats)));
         }
         Ok(())
     }
@@ -1,4 +1,4 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 pub use quake_serverinfo::Settings;
 
 use crate::bytesextra;
@@ -11,11 +11,11 @@ pub fn serverinfo_string(data: &[u8]) -> Result<String> {
     const MAX_OFFSET: usize = 256;
     const MAX_SIZE: usize = 1024;
     let Some((from, to)) = bytesextra::offsets_between(
-        &data[..MAX_OFFSET + MAX_SIZE],
+        &data[..data.len().min(MAX_OFFSET + MAX_SIZE)],
         br#"fullserverinfo ""#,
-        &[b'"'],
+        b"\"",
     ) else {
-        return Err(e!("Serverinfo not found"));
+        return Err(Error::MissingServerinfo);
     };
     Ok(quake_text::bytestr::to_utf8(&data[from..to]))
 }
@@ -29,6 +29,18 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_serverinfo_string() {
+        assert!(matches!(
+            serverinfo_string(b""),
+            Err(Error::MissingServerinfo)
+        ));
+        assert_eq!(
+            serverinfo_string(br#"fullserverinfo "\maxfps\77""#).unwrap(),
+            r#"\maxfps\77"#.to_string()
+        );
+    }
+
     #[test]
     fn test_server_settings() -> Result<()> {
         let data = read("tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd")?;
@@ -0,0 +1,59 @@
+use std::fs::{read, read_to_string, write};
+
+use anyhow::Result;
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    match_duration_info, players, result, rounds, server, serverinfo, teams, vote_events, Player,
+};
+
+const DEMOS: [&str; 3] = [
+    "2on2_sf_vs_red[frobodm2]220104-0915",
+    "duel_equ_vs_kaboom[povdmm4]20240422-1038",
+    "wipeout_red_vs_blue[q3dm6qw]20240406-2028",
+];
+
+// set UPDATE_SNAPSHOTS=1 to rewrite the snapshot files
+fn assert_snapshot<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let path = format!("tests/files/snapshots/{}.json", name);
+    let actual = serde_json::to_value(value)?;
+
+    if std::env::var("UPDATE_SNAPSHOTS").is_ok() {
+        write(&path, serde_json::to_string_pretty(&actual)? + "\n")?;
+    }
+
+    let expected: Value = serde_json::from_str(&read_to_string(&path)?)?;
+    assert_eq!(actual, expected, "{}", path);
+    Ok(())
+}
+
+#[test]
+fn test_snapshots() -> Result<()> {
+    for demo in DEMOS {
+        let data = read(format!("tests/files/{}.mvd", demo))?;
+        assert_snapshot(&format!("{}.players", demo), &players(&data)?)?;
+        assert_snapshot(&format!("{}.teams", demo), &teams(&data)?)?;
+        assert_snapshot(&format!("{}.server", demo), &server(&data).ok())?;
+        assert_snapshot(&format!("{}.serverinfo", demo), &serverinfo(&data)?)?;
+        assert_snapshot(&format!("{}.result", demo), &result(&data)?)?;
+        assert_snapshot(&format!("{}.duration", demo), &match_duration_info(&data)?)?;
+        assert_snapshot(&format!("{}.votes", demo), &vote_events(&data))?;
+        assert_snapshot(&format!("{}.rounds", demo), &rounds(&data))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_roundtrip() -> Result<()> {
+    let data = read(format!("tests/files/{}.mvd", DEMOS[1]))?;
+    let players = players(&data)?;
+    let json = serde_json::to_string(&players)?;
+    assert_eq!(serde_json::from_str::<Vec<Player>>(&json)?, players);
+
+    let result = result(&data)?;
+    let json = serde_json::to_string(&result)?;
+    assert_eq!(serde_json::from_str::<crate::MatchResult>(&json)?, result);
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 use ktxstats::v3::KtxstatsV3;
 
 use crate::players;
@@ -0,0 +1,29 @@
+// synthetic demo data for tests
+pub fn frame(duration: u8, target: u8, body: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![duration, target];
+    bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+pub fn to_all(duration: u8, body: &[u8]) -> Vec<u8> {
+    frame(duration, 6, body)
+}
+
+// sent to one player, e.g. svc_print from sprint
+pub fn to_single(duration: u8, number: u8, body: &[u8]) -> Vec<u8> {
+    frame(duration, 4 | (number << 3), body)
+}
+
+// multi target with empty mask
+pub fn hidden(body: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0, 3, 0, 0, 0, 0];
+    bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+// svc_print
+pub fn print(level: u8, content: &[u8]) -> Vec<u8> {
+    [&[8, level], content, &[0]].concat()
+}
@@ -1,4 +1,4 @@
-use anyhow::{anyhow as e, Result};
+use crate::error::{Error, Result};
 use chrono::{DateTime, LocalResult, TimeZone, Utc};
 
 use crate::matchdate::matchdate;
@@ -10,13 +10,13 @@ pub fn timestamp(data: &[u8]) -> Result<DateTime<Utc>> {
 
 pub fn timestamp_from_epoch(data: &[u8]) -> Result<DateTime<Utc>> {
     let Some(epoch) = serverinfo(data)?.epoch else {
-        return Err(e!("Epoch not found in serverinfo"));
+        return Err(Error::MissingEpoch);
     };
 
     match Utc.timestamp_opt(epoch as i64, 0) {
         LocalResult::Single(ts) => Ok(ts),
         LocalResult::Ambiguous(earliest, _) => Ok(earliest),
-        _ => Err(e!("Unable to parse timestamp from epoch")),
+        _ => Err(Error::InvalidTimestamp),
     }
 }
 
@@ -55,14 +55,12 @@ mod tests {
 
     #[test]
     fn test_timestamp_from_epoch() -> Result<()> {
-        assert_eq!(
+        assert!(matches!(
             timestamp_from_epoch(&read(
                 "tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd"
-            )?)
-            .unwrap_err()
-            .to_string(),
-            "Epoch not found in serverinfo".to_string()
-        );
+            )?),
+            Err(Error::MissingEpoch)
+        ));
 
         assert_eq!(
             timestamp_from_epoch(&read(
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use quake_clientinfo::Clientinfo;
+
+use crate::qw::frame;
+use crate::qw::message::{messages, Message};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NameChange {
+    pub index: usize,
+    pub time: Duration,
+    pub number: u8,
+    pub name: String, // empty when slot is cleared or taken by a spectator
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NameTimeline {
+    pub changes: Vec<NameChange>,
+}
+
+impl NameTimeline {
+    pub fn names_at(&self, index: usize) -> HashMap<u8, String> {
+        let mut names: HashMap<u8, String> = HashMap::new();
+
+        for change in self.changes.iter().take_while(|c| c.index <= index) {
+            if change.name.is_empty() {
+                names.remove(&change.number);
+            } else {
+                names.insert(change.number, change.name.clone());
+            }
+        }
+
+        names
+    }
+
+    pub fn number_at(&self, name: &str, index: usize) -> Option<u8> {
+        self.names_at(index)
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(number, _)| *number)
+    }
+
+    pub fn last_names(&self) -> HashMap<u8, String> {
+        let mut names: HashMap<u8, String> = HashMap::new();
+
+        for change in self.changes.iter().filter(|c| !c.name.is_empty()) {
+            names.insert(change.number, change.name.clone());
+        }
+
+        names
+    }
+}
+
+pub fn name_timeline(data: &[u8]) -> NameTimeline {
+    let mut index = 0;
+    let mut total_ms: u32 = 0;
+    let mut current: HashMap<u8, String> = HashMap::new();
+    let mut changes: Vec<NameChange> = vec![];
+
+    while let Ok(info) = frame::Info::from_data_and_index(data, index) {
+        total_ms += info.duration;
+
+        if info.body_size > 0 && !info.is_hidden {
+            for message in messages(&data[info.body_range.clone()]) {
+                let (number, name) = match message {
+                    Message::UpdateUserinfo(u) => {
+                        let info = Clientinfo::from(to_unicode(&u.userinfo).as_str());
+                        let name = match info.spectator.is_some_and(|v| v != 0) {
+                            true => String::new(),
+                            false => info.name.unwrap_or_default(),
+                        };
+                        (u.player_number, name)
+                    }
+                    Message::Setinfo(s) if s.key == b"name" => {
+                        (s.player_number, to_unicode(&s.value))
+                    }
+                    _ => continue,
+                };
+
+                if current.get(&number).map_or(name.is_empty(), |n| *n == name) {
+                    continue;
+                }
+
+                current.insert(number, name.clone());
+                changes.push(NameChange {
+                    index: info.index,
+                    time: Duration::from_millis(total_ms as u64),
+                    number,
+                    name,
+                });
+            }
+        }
+
+        index += info.size;
+    }
+
+    NameTimeline { changes }
+}
+
+fn to_unicode(value: &[u8]) -> String {
+    quake_text::bytestr::to_unicode(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn change(index: usize, number: u8, name: &str) -> NameChange {
+        NameChange {
+            index,
+            time: Duration::ZERO,
+            number,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_name_timeline() -> Result<()> {
+        {
+            let demo_data = read("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")?;
+            let timeline = name_timeline(&demo_data);
+            assert_eq!(
+                timeline.last_names(),
+                HashMap::from([(0, "eQu".to_string()), (2, "KabÏÏm".to_string())])
+            );
+        }
+        {
+            let demo_data = read("tests/files/ffa_5[dm4]20240501-1229.mvd")?;
+            let timeline = name_timeline(&demo_data);
+            assert_eq!(timeline.number_at("test", 200_000), Some(1));
+            assert_eq!(timeline.number_at("test", demo_data.len()), None);
+            assert_eq!(timeline.last_names().get(&1), Some(&"test".to_string()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_names_at() {
+        let timeline = NameTimeline {
+            changes: vec![
+                change(10, 0, "alpha"),
+                change(10, 1, "beta"),
+                change(50, 0, "gamma"),
+                change(90, 1, ""),
+            ],
+        };
+
+        assert_eq!(timeline.names_at(5), HashMap::new());
+        assert_eq!(
+            timeline.names_at(49),
+            HashMap::from([(0, "alpha".to_string()), (1, "beta".to_string())])
+        );
+        assert_eq!(
+            timeline.names_at(50),
+            HashMap::from([(0, "gamma".to_string()), (1, "beta".to_string())])
+        );
+        assert_eq!(timeline.number_at("alpha", 50), None);
+        assert_eq!(timeline.number_at("gamma", 50), Some(0));
+        assert_eq!(timeline.number_at("beta", 90), None);
+        assert_eq!(
+            timeline.last_names(),
+            HashMap::from([(0, "gamma".to_string()), (1, "beta".to_string())])
+        );
+    }
+}
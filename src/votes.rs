@@ -0,0 +1,472 @@
+use std::time::Duration;
+
+use quake_text::bytestr::{to_ascii, to_unicode};
+
+use crate::prints::{timed_prints, TimedPrint};
+use crate::qw::prot::PrintId;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoteKind {
+    StopMatch,
+    Map(String),
+    ElectAdmin,
+    ElectCaptain,
+    Ready, // players readying up before countdown
+    Break, // players unreadying before countdown
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoteStatus {
+    Cast,
+    Passed,
+    Failed, // timed out
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoteEvent {
+    pub index: usize,
+    pub time: Duration,
+    pub name: Option<String>, // voter, none if the vote passed or failed
+    pub kind: VoteKind,
+    pub status: VoteStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdminAction {
+    Kick(String),
+    Ban(String),
+    ForceStart,
+    ForceBreak,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdminEvent {
+    pub index: usize,
+    pub time: Duration,
+    pub name: String, // admin
+    pub action: AdminAction,
+}
+
+// KTX prints, "{name} <suffix>"
+const VOTE_CAST_SUFFIXES: [(&str, VoteKind); 3] = [
+    (" votes for stopping the match", VoteKind::StopMatch),
+    (" votes for admin election", VoteKind::ElectAdmin),
+    (" votes for captain election", VoteKind::ElectCaptain),
+];
+const VOTE_MAP_INFIX: &str = " would rather play on ";
+// "{name} <infix>" or "{name} <infix> [{team}]"
+const VOTE_READY_INFIXES: [(&str, VoteKind); 2] = [
+    (" is ready", VoteKind::Ready),
+    (" is not ready", VoteKind::Break),
+];
+const VOTE_PASSED_PRINTS: [(&str, VoteKind); 3] = [
+    ("Match stopped by majority vote", VoteKind::StopMatch),
+    ("Majority votes for mapchange", VoteKind::Map(String::new())),
+    ("All players ready", VoteKind::Ready),
+];
+const VOTE_TIMED_OUT_PRINT: &str = "The voting has timed out.";
+const VOTE_PASSED_SUFFIXES: [(&str, VoteKind); 2] = [
+    (" gains admin status!", VoteKind::ElectAdmin),
+    (" gains captain status!", VoteKind::ElectCaptain),
+];
+
+// KTX prints, "{target} <infix> {admin}" and "{admin} <suffix>"
+const ADMIN_KICK_INFIX: &str = " was kicked by ";
+const ADMIN_BAN_INFIX: &str = " was banned by ";
+const ADMIN_SUFFIXES: [(&str, AdminAction); 2] = [
+    (" forces matchstart!", AdminAction::ForceStart),
+    (" forces a break!", AdminAction::ForceBreak),
+];
+
+impl VoteEvent {
+    pub fn from_print(print: &TimedPrint) -> Option<Self> {
+        if !is_server_print(print) {
+            return None;
+        }
+
+        let (text, raw) = print_text(print);
+        let event = |name: Option<String>, kind: VoteKind, status: VoteStatus| VoteEvent {
+            index: print.index,
+            time: print.time,
+            name,
+            kind,
+            status,
+        };
+
+        for (suffix, kind) in VOTE_CAST_SUFFIXES.iter() {
+            if let Some(name) = text.strip_suffix(suffix) {
+                return Some(event(
+                    Some(to_unicode(&raw[..name.len()])),
+                    kind.clone(),
+                    VoteStatus::Cast,
+                ));
+            }
+        }
+
+        if let Some((name, map)) = text.split_once(VOTE_MAP_INFIX) {
+            let name = to_unicode(&raw[..name.len()]);
+            return Some(event(
+                Some(name),
+                VoteKind::Map(map.to_string()),
+                VoteStatus::Cast,
+            ));
+        }
+
+        for (infix, kind) in VOTE_READY_INFIXES.iter() {
+            if let Some((name, team)) = text.split_once(infix) {
+                if team.is_empty() || team.starts_with(' ') {
+                    let name = to_unicode(&raw[..name.len()]);
+                    return Some(event(Some(name), kind.clone(), VoteStatus::Cast));
+                }
+            }
+        }
+
+        for (content, kind) in VOTE_PASSED_PRINTS.iter() {
+            if text == *content {
+                return Some(event(None, kind.clone(), VoteStatus::Passed));
+            }
+        }
+
+        for (suffix, kind) in VOTE_PASSED_SUFFIXES.iter() {
+            if let Some(name) = text.strip_suffix(suffix) {
+                return Some(event(
+                    Some(to_unicode(&raw[..name.len()])),
+                    kind.clone(),
+                    VoteStatus::Passed,
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+impl AdminEvent {
+    pub fn from_print(print: &TimedPrint) -> Option<Self> {
+        if !is_server_print(print) {
+            return None;
+        }
+
+        let (text, raw) = print_text(print);
+        let event = |name: String, action: AdminAction| AdminEvent {
+            index: print.index,
+            time: print.time,
+            name,
+            action,
+        };
+
+        for (infix, to_action) in [
+            (
+                ADMIN_KICK_INFIX,
+                AdminAction::Kick as fn(String) -> AdminAction,
+            ),
+            (ADMIN_BAN_INFIX, AdminAction::Ban),
+        ] {
+            if let Some((target, _)) = text.split_once(infix) {
+                let admin_from = target.len() + infix.len();
+                let target = to_unicode(&raw[..target.len()]);
+                return Some(event(to_unicode(&raw[admin_from..]), to_action(target)));
+            }
+        }
+
+        for (suffix, action) in ADMIN_SUFFIXES.iter() {
+            if let Some(name) = text.strip_suffix(suffix) {
+                return Some(event(to_unicode(&raw[..name.len()]), action.clone()));
+            }
+        }
+
+        None
+    }
+}
+
+pub fn vote_events(data: &[u8]) -> Vec<VoteEvent> {
+    let mut events: Vec<VoteEvent> = vec![];
+    let mut last_map = String::new();
+    let mut pending: Vec<VoteKind> = vec![];
+
+    for print in timed_prints(data).iter().filter(|p| is_server_print(p)) {
+        // timeout does not say which vote failed, fail all pending votes
+        if print_text(print).0 == VOTE_TIMED_OUT_PRINT {
+            for kind in pending.drain(..) {
+                events.push(VoteEvent {
+                    index: print.index,
+                    time: print.time,
+                    name: None,
+                    kind,
+                    status: VoteStatus::Failed,
+                });
+            }
+            continue;
+        }
+
+        let Some(mut event) = VoteEvent::from_print(print) else {
+            continue;
+        };
+
+        // map change announcement does not include the map name
+        match &mut event.kind {
+            VoteKind::Map(map) if event.status == VoteStatus::Passed => *map = last_map.clone(),
+            VoteKind::Map(map) => last_map = map.clone(),
+            _ => {}
+        }
+
+        let is_same_vote =
+            |kind: &VoteKind| std::mem::discriminant(kind) == std::mem::discriminant(&event.kind);
+        match event.status {
+            // ready and break are not timed votes
+            VoteStatus::Cast if matches!(event.kind, VoteKind::Ready | VoteKind::Break) => {}
+            VoteStatus::Cast if !pending.iter().any(is_same_vote) => {
+                pending.push(event.kind.clone());
+            }
+            VoteStatus::Passed => pending.retain(|k| !is_same_vote(k)),
+            _ => {}
+        }
+
+        events.push(event);
+    }
+
+    events
+}
+
+pub fn admin_events(data: &[u8]) -> Vec<AdminEvent> {
+    timed_prints(data)
+        .iter()
+        .filter_map(AdminEvent::from_print)
+        .collect()
+}
+
+// votes are announced by the server, chat can contain the same text
+fn is_server_print(print: &TimedPrint) -> bool {
+    print.print.id == PrintId::High
+}
+
+// ascii text (for matching) and raw bytes (for names) without trailing newline
+fn print_text(print: &TimedPrint) -> (String, &[u8]) {
+    let content = print.print.content.as_slice();
+    let raw = content.strip_suffix(b"\n").unwrap_or(content);
+    (to_ascii(raw), raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::qw::message::Print;
+
+    use super::*;
+    use crate::testdemo::{print, to_all};
+
+    fn timed_print(content: &[u8]) -> TimedPrint {
+        TimedPrint {
+            index: 1,
+            time: Duration::from_secs(2),
+            print: Print {
+                id: PrintId::High,
+                content: content.to_vec(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_vote_event_from_print() {
+        assert_eq!(
+            VoteEvent::from_print(&timed_print(b"XantoM would rather play on dm3\n")),
+            Some(VoteEvent {
+                index: 1,
+                time: Duration::from_secs(2),
+                name: Some("XantoM".to_string()),
+                kind: VoteKind::Map("dm3".to_string()),
+                status: VoteStatus::Cast,
+            })
+        );
+        assert_eq!(
+            VoteEvent::from_print(&timed_print(b"XantoM gains captain status!\n")),
+            Some(VoteEvent {
+                index: 1,
+                time: Duration::from_secs(2),
+                name: Some("XantoM".to_string()),
+                kind: VoteKind::ElectCaptain,
+                status: VoteStatus::Passed,
+            })
+        );
+        assert_eq!(
+            VoteEvent::from_print(&timed_print(b"Final is ready \x10=SF=\x11\n")),
+            Some(VoteEvent {
+                index: 1,
+                time: Duration::from_secs(2),
+                name: Some("Final".to_string()),
+                kind: VoteKind::Ready,
+                status: VoteStatus::Cast,
+            })
+        );
+        assert_eq!(
+            VoteEvent::from_print(&timed_print(b"XantoM is not ready\n")),
+            Some(VoteEvent {
+                index: 1,
+                time: Duration::from_secs(2),
+                name: Some("XantoM".to_string()),
+                kind: VoteKind::Break,
+                status: VoteStatus::Cast,
+            })
+        );
+        assert_eq!(
+            VoteEvent::from_print(&timed_print(b"XantoM is readying\n")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_chat_is_ignored() {
+        let chat = |content: &[u8]| TimedPrint {
+            print: Print {
+                id: PrintId::Chat,
+                content: content.to_vec(),
+            },
+            ..timed_print(content)
+        };
+
+        assert_eq!(
+            VoteEvent::from_print(&chat(b"XantoM votes for stopping the match\n")),
+            None
+        );
+        assert_eq!(
+            AdminEvent::from_print(&chat(b"Milton was kicked by XantoM\n")),
+            None
+        );
+
+        let data = [
+            to_all(0, &print(3, b"FOO votes for stopping the match\n")),
+            to_all(0, &print(3, b"Match stopped by majority vote\n")),
+            to_all(0, &print(3, b"FOO forces a break!\n")),
+        ]
+        .concat();
+
+        assert_eq!(vote_events(&data), vec![]);
+        assert_eq!(admin_events(&data), vec![]);
+    }
+
+    #[test]
+    fn test_admin_event_from_print() {
+        assert_eq!(
+            AdminEvent::from_print(&timed_print(b"Milton was kicked by XantoM\n")),
+            Some(AdminEvent {
+                index: 1,
+                time: Duration::from_secs(2),
+                name: "XantoM".to_string(),
+                action: AdminAction::Kick("Milton".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminEvent::from_print(&timed_print(b"XantoM forces matchstart!\n")),
+            Some(AdminEvent {
+                index: 1,
+                time: Duration::from_secs(2),
+                name: "XantoM".to_string(),
+                action: AdminAction::ForceStart,
+            })
+        );
+        assert_eq!(
+            AdminEvent::from_print(&timed_print(b"XantoM is ready\n")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_vote_events() -> Result<()> {
+        assert_eq!(
+            vote_events(&read("tests/files/ffa_5[dm4]20240501-1229.mvd")?),
+            vec![
+                VoteEvent {
+                    index: 7169,
+                    time: Duration::from_millis(116),
+                    name: Some("test".to_string()),
+                    kind: VoteKind::Ready,
+                    status: VoteStatus::Cast,
+                },
+                VoteEvent {
+                    index: 7169,
+                    time: Duration::from_millis(116),
+                    name: None,
+                    kind: VoteKind::Ready,
+                    status: VoteStatus::Passed,
+                },
+                VoteEvent {
+                    index: 532767,
+                    time: Duration::from_millis(71503),
+                    name: Some("test".to_string()),
+                    kind: VoteKind::StopMatch,
+                    status: VoteStatus::Cast,
+                },
+                VoteEvent {
+                    index: 532767,
+                    time: Duration::from_millis(71503),
+                    name: None,
+                    kind: VoteKind::StopMatch,
+                    status: VoteStatus::Passed,
+                },
+            ]
+        );
+        assert_eq!(
+            vote_events(&read(
+                "tests/files/duel_holy_vs_dago[bravado]20240426-1659.mvd"
+            )?),
+            vec![
+                VoteEvent {
+                    index: 6343,
+                    time: Duration::from_millis(95),
+                    name: Some("äáçï".to_string()),
+                    kind: VoteKind::Ready,
+                    status: VoteStatus::Cast,
+                },
+                VoteEvent {
+                    index: 6343,
+                    time: Duration::from_millis(95),
+                    name: None,
+                    kind: VoteKind::Ready,
+                    status: VoteStatus::Passed,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vote_events_timed_out() {
+        let data = [
+            to_all(0, &print(2, b"FOO is ready\n")),
+            to_all(0, &print(2, b"FOO votes for admin election\n")),
+            to_all(100, &print(2, b"The voting has timed out.\n")),
+            to_all(0, &print(2, b"All players ready\n")),
+        ]
+        .concat();
+        let event = |name: Option<&str>, kind: VoteKind, status: VoteStatus, time: u64| VoteEvent {
+            index: 0,
+            time: Duration::from_millis(time),
+            name: name.map(String::from),
+            kind,
+            status,
+        };
+
+        assert_eq!(
+            vote_events(&data)
+                .into_iter()
+                .map(|e| VoteEvent { index: 0, ..e })
+                .collect::<Vec<_>>(),
+            vec![
+                event(Some("FOO"), VoteKind::Ready, VoteStatus::Cast, 0),
+                event(Some("FOO"), VoteKind::ElectAdmin, VoteStatus::Cast, 0),
+                event(None, VoteKind::ElectAdmin, VoteStatus::Failed, 100),
+                event(None, VoteKind::Ready, VoteStatus::Passed, 100),
+            ]
+        );
+    }
+}
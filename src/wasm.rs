@@ -0,0 +1,72 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+// plain js objects and arrays (not Map), timestamps as RFC 3339 strings
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    value
+        .serialize(&serializer)
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn players(data: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&crate::players::players(data)?)
+}
+
+#[wasm_bindgen]
+pub fn teams(data: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&crate::teams::teams(data)?)
+}
+
+#[wasm_bindgen]
+pub fn server(data: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&crate::server::server(data)?)
+}
+
+#[wasm_bindgen]
+pub fn timestamp(data: &[u8]) -> Result<String, JsError> {
+    Ok(crate::timestamp::timestamp(data)?.to_rfc3339())
+}
+
+// in seconds
+#[wasm_bindgen(js_name = matchDuration)]
+pub fn match_duration(data: &[u8]) -> Result<f64, JsError> {
+    Ok(crate::duration::match_duration(data)?.as_secs_f64())
+}
+
+#[wasm_bindgen(js_name = isValid)]
+pub fn is_valid(data: &[u8]) -> bool {
+    crate::validate::is_valid(data)
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::player::Player;
+    use crate::team::Team;
+
+    use super::*;
+
+    const DEMO: &[u8] =
+        include_bytes!("../tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd");
+
+    #[wasm_bindgen_test]
+    fn test_wasm() {
+        assert!(players(&[]).is_err());
+        assert!(!is_valid(&[]));
+
+        let players: Vec<Player> = serde_wasm_bindgen::from_value(players(DEMO).unwrap()).unwrap();
+        assert_eq!(players, crate::players::players(DEMO).unwrap());
+
+        let teams: Vec<Team> = serde_wasm_bindgen::from_value(teams(DEMO).unwrap()).unwrap();
+        assert_eq!(teams, crate::teams::teams(DEMO).unwrap());
+
+        assert!(server(DEMO).unwrap().is_object());
+        assert_eq!(timestamp(DEMO).unwrap(), "2024-04-22T10:38:20+00:00");
+        assert_eq!(match_duration(DEMO).unwrap(), 180.0);
+        assert!(is_valid(DEMO));
+    }
+}
@@ -0,0 +1,102 @@
+// usage: test_capi <duel demo>, exits with 0 if all checks pass
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "mvdparser.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                            \
+    do {                                                       \
+        if (!(cond)) {                                         \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #cond); \
+            failures++;                                        \
+        }                                                      \
+    } while (0)
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        return NULL;
+    }
+
+    fseek(file, 0, SEEK_END);
+    *len = (size_t)ftell(file);
+    fseek(file, 0, SEEK_SET);
+
+    uint8_t *data = malloc(*len);
+    if (data && fread(data, 1, *len, file) != *len) {
+        free(data);
+        data = NULL;
+    }
+
+    fclose(file);
+    return data;
+}
+
+int main(int argc, char **argv) {
+    if (argc < 2) {
+        fprintf(stderr, "usage: %s <demo>\n", argv[0]);
+        return 2;
+    }
+
+    size_t len = 0;
+    uint8_t *data = read_file(argv[1], &len);
+    if (!data) {
+        fprintf(stderr, "unable to read %s\n", argv[1]);
+        return 2;
+    }
+
+    MvdDemo *demo = mvd_open(data, len);
+    free(data); // copied by mvd_open
+    CHECK(demo != NULL);
+
+    char *map = mvd_map(demo);
+    CHECK(map && strcmp(map, "povdmm4") == 0);
+    mvd_string_free(map);
+
+    char *mode = mvd_mode(demo);
+    CHECK(mode && strcmp(mode, "1on1") == 0);
+    mvd_string_free(mode);
+
+    CHECK(mvd_match_duration_ms(demo) == 180000);
+
+    MvdPlayerList *players = mvd_players(demo);
+    CHECK(players && players->len == 2);
+    if (players && players->len == 2) {
+        CHECK(strcmp(players->items[1].name, "eQu") == 0);
+        CHECK(strcmp(players->items[1].team, "red") == 0);
+        CHECK(players->items[1].frags == 19);
+        CHECK(!players->items[1].is_bot);
+    }
+    mvd_players_free(players);
+
+    MvdTeamList *teams = mvd_teams(demo);
+    CHECK(teams && teams->len == 2);
+    if (teams) {
+        int frags = 0;
+        for (size_t i = 0; i < teams->len; i++) {
+            frags += teams->items[i].frags;
+            CHECK(teams->items[i].players.len == 1);
+        }
+        CHECK(frags == 39);
+    }
+    mvd_teams_free(teams);
+
+    mvd_close(demo);
+
+    // errors
+    MvdDemo *empty = mvd_open((const uint8_t *)"", 0);
+    CHECK(mvd_players(empty) == NULL);
+    CHECK(mvd_last_error() && strcmp(mvd_last_error(), "Unable to find clientinfo strings") == 0);
+    mvd_close(empty);
+    CHECK(mvd_match_duration_ms(NULL) == -1);
+    CHECK(mvd_open(NULL, 0) == NULL);
+    mvd_close(NULL);
+
+    if (failures == 0) {
+        printf("ok\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
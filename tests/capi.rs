@@ -0,0 +1,44 @@
+// builds the static library, then compiles and runs tests/c/test_capi.c against it
+#![cfg(all(feature = "capi", unix))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn run(command: &mut Command) {
+    let status = command.status().expect("unable to run command");
+    assert!(status.success(), "{:?} failed with {}", command, status);
+}
+
+#[test]
+fn test_c_program() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("capi");
+
+    // separate target dir, the outer cargo holds the lock of the default one
+    run(Command::new(env!("CARGO"))
+        .current_dir(manifest_dir)
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "capi",
+            "--crate-type",
+            "staticlib",
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir));
+
+    let program = target_dir.join("test_capi");
+    run(
+        Command::new(std::env::var("CC").unwrap_or("cc".to_string()))
+            .arg(manifest_dir.join("tests/c/test_capi.c"))
+            .arg("-I")
+            .arg(env!("OUT_DIR"))
+            .arg(target_dir.join("debug/libmvdparser.a"))
+            .args(["-lpthread", "-ldl", "-lm", "-o"])
+            .arg(&program),
+    );
+
+    run(Command::new(&program)
+        .arg(manifest_dir.join("tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd")));
+}
@@ -0,0 +1,82 @@
+// runs the mvdparser binary on demos in tests/files
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+const VALID: &str = "tests/files/duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd";
+const ABORTED: &str = "tests/files/2on2_sf_vs_red[frobodm2]220104-0915.mvd";
+const TRUNCATED: &str = "tests/files/4on4_-s-_vs_pol[dm2]20241118-2135.mvd";
+
+fn mvdparser(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mvdparser"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .output()
+        .expect("unable to run mvdparser")
+}
+
+fn exit_code(args: &[&str]) -> Option<i32> {
+    mvdparser(args).status.code()
+}
+
+fn invalid_demo() -> String {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("invalid.mvd");
+    std::fs::write(&path, b"not a demo").unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn test_validate_exit_codes() {
+    let invalid = invalid_demo();
+
+    assert_eq!(exit_code(&["validate", VALID]), Some(0));
+    assert_eq!(exit_code(&["validate", "tests/files/missing.mvd"]), Some(1));
+    assert_eq!(exit_code(&["validate", &invalid]), Some(3));
+    assert_eq!(exit_code(&["validate", TRUNCATED]), Some(4));
+    assert_eq!(exit_code(&["validate", ABORTED]), Some(5));
+
+    // worst demo
+    assert_eq!(exit_code(&["validate", VALID, ABORTED, TRUNCATED]), Some(4));
+}
+
+#[test]
+fn test_exit_codes_of_other_commands() {
+    assert_eq!(exit_code(&["info", VALID, ABORTED, TRUNCATED]), Some(0));
+    assert_eq!(exit_code(&["frags", ABORTED]), Some(0));
+    assert_eq!(exit_code(&["frags", TRUNCATED]), Some(0));
+    assert_eq!(exit_code(&["info", &invalid_demo()]), Some(1));
+    assert_eq!(
+        exit_code(&["info", VALID, "tests/files/missing.mvd"]),
+        Some(1)
+    );
+    assert_eq!(exit_code(&["info", "--csv", "--json", VALID]), Some(2));
+}
+
+#[test]
+fn test_json_output() {
+    let output = mvdparser(&["info", "--json", ABORTED]);
+    let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(value[0]["file"], ABORTED);
+    assert_eq!(value[0]["data"]["map"], "frobodm2");
+    assert_eq!(value[0]["data"]["status"], "aborted");
+    assert_eq!(
+        value[0]["data"]["players"].as_array().map(|p| p.len()),
+        Some(4)
+    );
+}
+
+#[test]
+fn test_csv_output() {
+    let output = mvdparser(&["frags", "--csv", VALID, ABORTED]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "file,name,frags");
+    assert_eq!(lines[1], format!("{},KabOOm,20", VALID));
+    assert_eq!(lines[6], format!("{},> MrJustice,-6", ABORTED));
+}
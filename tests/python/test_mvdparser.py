@@ -0,0 +1,75 @@
+from pathlib import Path
+
+import pytest
+
+import mvdparser
+
+FILES = Path(__file__).parent.parent / "files"
+DUEL = FILES / "duel_equ_vs_kaboom[povdmm4]20240422-1038.mvd"
+
+
+@pytest.fixture
+def demo():
+    return mvdparser.Demo.from_file(DUEL)
+
+
+def test_demo(demo):
+    assert len(demo) == DUEL.stat().st_size
+    assert len(mvdparser.Demo(DUEL.read_bytes())) == len(demo)
+
+    with pytest.raises(OSError):
+        mvdparser.Demo.from_file(FILES / "missing.mvd")
+
+
+def test_summary(demo):
+    assert demo.map == "povdmm4"
+    assert demo.mode == "1on1"
+    assert demo.hostname == "de.quake.world:27502 [QW-Group]"
+    assert demo.duration == pytest.approx(180.0)
+
+    empty = mvdparser.Demo(b"")
+    assert empty.map is None
+    assert empty.duration is None
+
+
+def test_players(demo):
+    players = demo.players()
+    assert [p.name for p in players] == ["KabÏÏm", "eQu"]
+
+    equ = players[1]
+    assert equ.team == "red"
+    assert equ.frags == 19
+    assert equ.color == (4, 4)
+    assert not equ.is_bot
+    assert repr(equ) == 'Player(name="eQu", team="red", frags=19)'
+
+    assert [p.name for p in mvdparser.players(DUEL.read_bytes())] == ["KabÏÏm", "eQu"]
+
+    with pytest.raises(ValueError, match="clientinfo"):
+        mvdparser.players(b"")
+
+
+def test_teams(demo):
+    teams = demo.teams()
+    assert len(teams) == 2
+    assert sum(t.frags for t in teams) == 39
+    assert all(isinstance(p, mvdparser.Player) for t in teams for p in t.players)
+
+
+def test_frag_timeline(demo):
+    timeline = demo.frag_timeline()
+    assert len(timeline) == 46
+    assert timeline == sorted(timeline, key=lambda u: u.time)
+
+    last = timeline[-1]
+    assert last.name == "KabÏÏm"
+    assert last.frags == 20
+    assert last.time == pytest.approx(187.169)
+
+
+def test_prints(demo):
+    prints = demo.prints()
+    assert len(prints) == 124
+    assert prints[-1].level == "chat"
+    assert prints[-1].content == "KabÏÏm: gg\n"
+    assert prints[-1].time == pytest.approx(195.504)